@@ -0,0 +1,33 @@
+use std::{
+    fs::File,
+    io::{self, BufReader},
+    path::Path,
+};
+
+/// Opens `file_loc` for buffered reading, naming the file in the error.
+pub fn file_reader(file_loc: impl AsRef<Path>) -> io::Result<BufReader<File>> {
+    let file_loc = file_loc.as_ref();
+    let file = File::open(file_loc)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file_loc.display(), e)))?;
+    Ok(BufReader::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+
+    #[test]
+    fn test_file_reader() {
+        let reader = file_reader(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).unwrap();
+        assert_eq!(reader.lines().next().unwrap().unwrap(), "[package]");
+    }
+
+    #[test]
+    fn test_file_reader_missing_file() {
+        let error = file_reader("./does_not_exist.txt").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().starts_with("./does_not_exist.txt: "));
+    }
+}
@@ -1,2 +1,18 @@
+use std::io::{self, Read};
+
 pub mod part_one;
 pub mod part_two;
+pub mod scanner;
+
+/// Reads a whole calibration document from any reader.
+pub fn read_input(mut reader: impl Read) -> io::Result<String> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    Ok(input)
+}
+
+/// Borrowed lines of a calibration document, without a leading UTF-8 byte
+/// order mark and with both `\n` and `\r\n` line endings accepted.
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    input.strip_prefix('\u{feff}').unwrap_or(input).lines()
+}
@@ -1,4 +1,4 @@
-/**
+/*
  * --- Day 1: Trebuchet?! ---
  * Something is wrong with global snow production, and you've been selected to take a look. The Elves have even given you a map; on it, they've used stars to mark the top fifty locations that are likely to be having problems.
  * You've been doing this long enough to know that to restore snow operations, you need to check all fifty stars by December 25th.
@@ -15,80 +15,184 @@
  * Consider your entire calibration document. What is the sum of all of the calibration values?
 */
 
+use crate::{lines, scanner::Scanner};
+
+/// How the first and last digit of a line are found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Single pass of the digits-only [`Scanner`].
+    #[default]
+    Scanner,
+    /// Searches the characters forwards and then backwards for a digit.
+    CharSearch,
+}
+
 pub struct PartOne<'a> {
-    calibration_values: &'a Vec<String>,
+    input: &'a str,
+    strategy: Strategy,
+    scanner: Scanner,
 }
 
 impl<'a> PartOne<'a> {
-    pub fn new(calibration_values: &'a Vec<String>) -> Self {
-        Self { calibration_values }
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            strategy: Strategy::default(),
+            scanner: Scanner::digits(),
+        }
+    }
+
+    pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn run(&self) -> i32 {
-        let result = self.calibration_values.iter().fold(0, |acc, x| {
-            let num = Self::get_first_last_int_from_str(x);
-            acc + num
-        });
-        result
+        lines(self.input)
+            .map(|line| self.get_first_last_int_from_str(line))
+            .sum()
     }
 
-    fn get_first_last_int_from_str(string: &str) -> i32 {
-        let mut first_digit: i32 = 0;
-        let mut last_digit: i32 = 0;
-        let mut first_digit_found: bool = false;
-        for (_, c) in string.chars().enumerate() {
-            if c.is_digit(10) && !first_digit_found {
-                first_digit = c.to_digit(10).unwrap() as i32;
-                first_digit_found = true;
-            }
-            if c.is_digit(10) && first_digit_found {
-                last_digit = c.to_digit(10).unwrap() as i32;
-            }
+    fn get_first_last_int_from_str(&self, string: &str) -> i32 {
+        match self.strategy {
+            Strategy::Scanner => self
+                .scanner
+                .calibration_value(string)
+                .expect("two digits always fit in a u32") as i32,
+            Strategy::CharSearch => Self::char_search(string),
+        }
+    }
+
+    fn char_search(string: &str) -> i32 {
+        let first_digit = string.chars().find_map(|c| c.to_digit(10));
+        let last_digit = string.chars().rev().find_map(|c| c.to_digit(10));
+
+        match (first_digit, last_digit) {
+            (Some(first), Some(last)) => (first * 10 + last) as i32,
+            _ => 0,
         }
-        let two_digit_number = format!("{}{}", first_digit, last_digit);
-        let two_digit_number = two_digit_number.parse::<i32>().unwrap();
-        two_digit_number
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::{prop_assert_eq, proptest};
+
     use super::*;
 
+    const STRATEGIES: [Strategy; 2] = [Strategy::Scanner, Strategy::CharSearch];
+
     #[test]
     fn test_get_first_last_int_from_str() {
-        let string_one = "7jlncfksix7rjgrpglmn9";
-        let result_one = PartOne::get_first_last_int_from_str(string_one);
-        assert_eq!(result_one, 79);
+        for strategy in STRATEGIES {
+            let part_one = PartOne::new("").with_strategy(strategy);
+
+            let string_one = "7jlncfksix7rjgrpglmn9";
+            let result_one = part_one.get_first_last_int_from_str(string_one);
+            assert_eq!(result_one, 79);
 
-        let string_two = "vcgkgxninerqjltdbhqzzpd4nine23";
-        let result_two = PartOne::get_first_last_int_from_str(string_two);
-        assert_eq!(result_two, 43);
+            let string_two = "vcgkgxninerqjltdbhqzzpd4nine23";
+            let result_two = part_one.get_first_last_int_from_str(string_two);
+            assert_eq!(result_two, 43);
 
-        let string_three = "fx3";
-        let result_three = PartOne::get_first_last_int_from_str(string_three);
-        assert_eq!(result_three, 33);
+            let string_three = "fx3";
+            let result_three = part_one.get_first_last_int_from_str(string_three);
+            assert_eq!(result_three, 33);
 
-        let string_four = "8nrbjbpjpnineseven";
-        let result_four = PartOne::get_first_last_int_from_str(string_four);
-        assert_eq!(result_four, 88);
+            let string_four = "8nrbjbpjpnineseven";
+            let result_four = part_one.get_first_last_int_from_str(string_four);
+            assert_eq!(result_four, 88);
 
-        let string_five = "7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo";
-        let result_five = PartOne::get_first_last_int_from_str(string_five);
-        assert_eq!(result_five, 76);
+            let string_five = "7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo";
+            let result_five = part_one.get_first_last_int_from_str(string_five);
+            assert_eq!(result_five, 76);
+
+            let string_six = "trebuchet";
+            let result_six = part_one.get_first_last_int_from_str(string_six);
+            assert_eq!(result_six, 0);
+        }
     }
 
     #[test]
     fn test_run() {
-        let calibration_values = vec![
-            "7jlncfksix7rjgrpglmn9".to_string(),
-            "vcgkgxninerqjltdbhqzzpd4nine23".to_string(),
-            "fx3".to_string(),
-            "8nrbjbpjpnineseven".to_string(),
-            "7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo".to_string(),
-        ];
-        let part_one = PartOne::new(&calibration_values);
+        let calibration_values = "7jlncfksix7rjgrpglmn9
+vcgkgxninerqjltdbhqzzpd4nine23
+fx3
+8nrbjbpjpnineseven
+7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo
+";
+        let part_one = PartOne::new(calibration_values);
         let result = part_one.run();
         assert_eq!(result, 79 + 43 + 33 + 88 + 76);
     }
+
+    #[test]
+    fn test_run_crlf_and_bom() {
+        let calibration_values = "\u{feff}1abc2\r\npqr3stu8vwx\r\na1b2c3d4e5f\r\ntreb7uchet\r\n";
+        for strategy in STRATEGIES {
+            let part_one = PartOne::new(calibration_values).with_strategy(strategy);
+            let result = part_one.run();
+            assert_eq!(result, 142);
+        }
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let input = include_str!("../input.txt");
+        let scanner = PartOne::new(input).with_strategy(Strategy::Scanner);
+        let char_search = PartOne::new(input).with_strategy(Strategy::CharSearch);
+        for line in lines(input) {
+            assert_eq!(
+                scanner.get_first_last_int_from_str(line),
+                char_search.get_first_last_int_from_str(line),
+                "{}",
+                line
+            );
+        }
+        assert_eq!(scanner.run(), char_search.run());
+    }
+
+    #[test]
+    fn test_read_input() {
+        let reader: &[u8] = b"\xef\xbb\xbf1abc2\r\ntreb7uchet";
+        let input = crate::read_input(reader).unwrap();
+        let part_one = PartOne::new(&input);
+        assert_eq!(part_one.run(), 12 + 77);
+    }
+
+    /// Looks at every line byte by byte for the first and last ASCII digit.
+    fn reference(input: &str) -> i32 {
+        input
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .map(|line| {
+                let digits: Vec<i32> = line
+                    .bytes()
+                    .filter(u8::is_ascii_digit)
+                    .map(|digit| (digit - b'0') as i32)
+                    .collect();
+                match (digits.first(), digits.last()) {
+                    (Some(first), Some(last)) => first * 10 + last,
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn run_never_panics(input in "\\PC*") {
+            for strategy in STRATEGIES {
+                PartOne::new(&input).with_strategy(strategy).run();
+            }
+        }
+
+        #[test]
+        fn agrees_with_reference(input in "[a-z0-9]{0,16}(\r?\n[a-z0-9]{0,16}){0,16}") {
+            for strategy in STRATEGIES {
+                prop_assert_eq!(PartOne::new(&input).with_strategy(strategy).run(), reference(&input));
+            }
+        }
+    }
 }
@@ -1,4 +1,4 @@
-/**
+/*
  * Your calculation isn't quite right. It looks like some of the digits are actually spelled out with letters: one, two, three, four, five, six, seven, eight, and nine also count as valid "digits".
  * Equipped with this new information, you now need to find the real first and last digit on each line. For example:
  * two1nine
@@ -11,270 +11,311 @@
  * In this example, the calibration values are 29, 83, 13, 24, 42, 14, and 76. Adding these together produces 281.
  */
 
-const NUMBERS: [(&str, u32); 9] =
-    [
-        ("one", 1),
-        ("two", 2),
-        ("three", 3),
-        ("four", 4),
-        ("five", 5),
-        ("six", 6),
-        ("seven", 7),
-        ("eight", 8),
-        ("nine", 9),
-    ];
+use crate::{lines, scanner::Scanner};
+
+pub const NUMBERS: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
 
 pub struct PartTwo<'a> {
-    calibration_values: &'a Vec<String>,
+    input: &'a str,
+    scanner: Scanner,
 }
 
 impl<'a> PartTwo<'a> {
-    pub fn new(calibration_values: &'a Vec<String>) -> Self {
-        Self { calibration_values }
-    }
-
-    pub fn run(&self) -> u32 {
-        let result = self.calibration_values.iter().fold(0, |acc, x| {
-            let num = Self::get_first_last_int_from_str(x);
-            acc + num
-        });
-        result
-    }
-
-    fn find_lowest_index_number_from_str(string: &str) -> Option<(u32, usize)> {
-        let mut lowest_index: Option<usize> = None;
-        let mut lowest_index_number: Option<u32> = None;
-        for (_, (number, value)) in NUMBERS.into_iter().enumerate() {
-            let idx = string.find(number);
-            match idx {
-                Some(idx) => {
-                    if lowest_index.is_none() || idx < lowest_index? {
-                        lowest_index = Some(idx);
-                        lowest_index_number = Some(value);
-                    }
-                }
-                None => {}
-            };
-        }
-        match (lowest_index, lowest_index_number) {
-            (Some(lowest_index), Some(lowest_index_number)) => {
-                Some((lowest_index_number, lowest_index))
-            }
-            _ => None,
-        }
+    pub fn new(input: &'a str) -> Self {
+        let digits = (1..10).map(|digit| (digit.to_string(), digit));
+        let numbers = NUMBERS
+            .iter()
+            .map(|&(number, value)| (number.to_string(), value));
+        Self::with_vocabulary(input, digits.chain(numbers))
     }
 
-    fn find_highest_index_number_from_str(string: &str) -> Option<(u32, usize)> {
-        let mut highest_index: Option<usize> = None;
-        let mut highest_index_number: Option<u32> = None;
-        for (_, (number, value)) in NUMBERS.into_iter().enumerate() {
-            let idx = string.rfind(number);
-            match idx {
-                Some(idx) => {
-                    if highest_index.is_none() || idx > highest_index? {
-                        highest_index = Some(idx);
-                        highest_index_number = Some(value);
-                    }
-                }
-                None => {}
-            };
-        }
-        match (highest_index, highest_index_number) {
-            (Some(highest_index), Some(highest_index_number)) => {
-                Some((highest_index_number, highest_index))
-            }
-            _ => None,
+    /// Uses `vocabulary` instead of the English digits and number words, e.g.
+    /// to add "zero", other languages' number words or multi-digit values.
+    pub fn with_vocabulary<I, S>(input: &'a str, vocabulary: I) -> Self
+    where
+        I: IntoIterator<Item = (S, u32)>,
+        S: AsRef<str>,
+    {
+        Self {
+            input,
+            scanner: Scanner::new(vocabulary),
         }
     }
 
-    fn find_lowest_index_int_from_str(string: &str) -> Option<(u32, usize)> {
-        for (i, c) in string.chars().enumerate() {
-            if c.is_digit(10) {
-                let c = c.to_digit(10).unwrap() as u32;
-                return Some((c, i));
-            }
-        }
-        None
-    }
-
-    fn find_highest_index_int_from_str(string: &str) -> Option<(u32, usize)> {
-        for (i, c) in string.chars().rev().enumerate() {
-            if c.is_digit(10) {
-                let c = c.to_digit(10).unwrap() as u32;
-                return Some((c, string.len() - i));
-            }
-        }
-        None
+    #[tracing::instrument(skip(self))]
+    pub fn run(&self) -> Result<u32, String> {
+        lines(self.input)
+            .enumerate()
+            .try_fold(0u32, |sum, (index, line)| {
+                let value = self
+                    .get_first_last_int_from_str(line)
+                    .map_err(|error| format!("line {}: {}", index + 1, error))?;
+                sum.checked_add(value)
+                    .ok_or_else(|| format!("line {}: the sum overflows a u32", index + 1))
+            })
     }
 
-    fn get_first_last_int_from_str(string: &str) -> u32 {
-        let first_number = Self::find_lowest_index_number_from_str(string);
-        let last_number = Self::find_highest_index_number_from_str(string);
-
-        let first_int = Self::find_lowest_index_int_from_str(string);
-        let last_int = Self::find_highest_index_int_from_str(string);
-
-        let first_digit = match (first_int, first_number) {
-            (Some((first_int, first_int_index)), Some((first_number, first_number_index))) => {
-                if first_int_index < first_number_index {
-                    first_int
-                } else {
-                    first_number
-                }
-            }
-            (Some((first_int, _)), None) => first_int,
-            (None, Some((first_number, _))) => first_number,
-            (None, None) => 0,
-        };
-        let last_digit = match (last_int, last_number) {
-            (Some((last_int, last_int_index)), Some((last_number, last_number_index))) => {
-                if last_int_index > last_number_index {
-                    last_int
-                } else {
-                    last_number
-                }
-            }
-            (Some((last_int, _)), None) => last_int,
-            (None, Some((last_number, _))) => last_number,
-            (None, None) => 0,
-        };
-
-        let two_digit_number = format!("{}{}", first_digit, last_digit);
-        let two_digit_number = two_digit_number.parse::<u32>().unwrap();
-        two_digit_number
+    fn get_first_last_int_from_str(&self, string: &str) -> Result<u32, String> {
+        self.scanner.calibration_value(string)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
+    fn first(scanner: &Scanner, string: &str) -> Option<(u32, usize)> {
+        scanner
+            .first_last(string)
+            .map(|(first, _)| (first.value, first.start))
+    }
+
     #[test]
     fn test_find_lowest_index_number_from_str() {
+        let scanner = Scanner::new(NUMBERS);
+
         let string_one = "7jlncfksix7rjgrpglmn9";
-        let result_one = PartTwo::find_lowest_index_number_from_str(string_one);
+        let result_one = first(&scanner, string_one);
         assert_eq!(result_one, Some((6, 7)));
 
         let string_two = "vcgkgxninerqjltdbhqzzpd4nine23";
-        let result_two = PartTwo::find_lowest_index_number_from_str(string_two);
+        let result_two = first(&scanner, string_two);
         assert_eq!(result_two, Some((9, 6)));
 
         let string_three = "fx3";
-        let result_three = PartTwo::find_lowest_index_number_from_str(string_three);
+        let result_three = first(&scanner, string_three);
         assert_eq!(result_three, None);
 
         let string_four = "8nrbjbpjpnineseven";
-        let result_four = PartTwo::find_lowest_index_number_from_str(string_four);
+        let result_four = first(&scanner, string_four);
         assert_eq!(result_four, Some((9, 9)));
 
         let string_five = "7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo";
-        let result_five = PartTwo::find_lowest_index_number_from_str(string_five);
+        let result_five = first(&scanner, string_five);
         assert_eq!(result_five, Some((8, 20)));
     }
 
     #[test]
     fn test_find_highest_index_number_from_str() {
+        let scanner = Scanner::new(NUMBERS);
+        let last = |string| {
+            scanner
+                .first_last(string)
+                .map(|(_, last)| (last.value, last.start))
+        };
+
         let string_one = "7jlncfksix7rjgrpglmn9";
-        let result_one = PartTwo::find_highest_index_number_from_str(string_one);
+        let result_one = last(string_one);
         assert_eq!(result_one, Some((6, 7)));
 
         let string_two = "vcgkgxninerqjltdbhqzzpd4nine23";
-        let result_two = PartTwo::find_highest_index_number_from_str(string_two);
+        let result_two = last(string_two);
         assert_eq!(result_two, Some((9, 24)));
 
         let string_three = "fx3";
-        let result_three = PartTwo::find_highest_index_number_from_str(string_three);
+        let result_three = last(string_three);
         assert_eq!(result_three, None);
 
         let string_four = "8nrbjbpjpnineseven";
-        let result_four = PartTwo::find_highest_index_number_from_str(string_four);
+        let result_four = last(string_four);
         assert_eq!(result_four, Some((7, 13)));
 
         let string_five = "7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo";
-        let result_five = PartTwo::find_highest_index_number_from_str(string_five);
+        let result_five = last(string_five);
         assert_eq!(result_five, Some((2, 40)));
     }
 
     #[test]
     fn test_find_lowest_index_int_from_str() {
+        let scanner = Scanner::digits();
+
         let string_one = "7jlncfksix7rjgrpglmn9";
-        let result_one = PartTwo::find_lowest_index_int_from_str(string_one);
+        let result_one = first(&scanner, string_one);
         assert_eq!(result_one, Some((7, 0)));
 
         let string_two = "vcgkgxninerqjltdbhqzzpd4nine23";
-        let result_two = PartTwo::find_lowest_index_int_from_str(string_two);
+        let result_two = first(&scanner, string_two);
         assert_eq!(result_two, Some((4, 23)));
 
         let string_three = "fx3";
-        let result_three = PartTwo::find_lowest_index_int_from_str(string_three);
+        let result_three = first(&scanner, string_three);
         assert_eq!(result_three, Some((3, 2)));
 
         let string_four = "8nrbjbpjpnineseven";
-        let result_four = PartTwo::find_lowest_index_int_from_str(string_four);
+        let result_four = first(&scanner, string_four);
         assert_eq!(result_four, Some((8, 0)));
 
         let string_five = "7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo";
-        let result_five = PartTwo::find_lowest_index_int_from_str(string_five);
+        let result_five = first(&scanner, string_five);
         assert_eq!(result_five, Some((7, 0)));
     }
 
     #[test]
     fn test_find_highest_index_int_from_str() {
+        let scanner = Scanner::digits();
+        let last = |string| {
+            scanner
+                .first_last(string)
+                .map(|(_, last)| (last.value, last.end))
+        };
+
         let string_one = "7jlncfksix7rjgrpglmn9";
-        let result_one = PartTwo::find_highest_index_int_from_str(string_one);
+        let result_one = last(string_one);
         assert_eq!(result_one, Some((9, 21)));
 
         let string_two = "vcgkgxninerqjltdbhqzzpd4nine23";
-        let result_two = PartTwo::find_highest_index_int_from_str(string_two);
+        let result_two = last(string_two);
         assert_eq!(result_two, Some((3, 30)));
 
         let string_three = "fx3";
-        let result_three = PartTwo::find_highest_index_int_from_str(string_three);
+        let result_three = last(string_three);
         assert_eq!(result_three, Some((3, 3)));
 
         let string_four = "8nrbjbpjpnineseven";
-        let result_four = PartTwo::find_highest_index_int_from_str(string_four);
+        let result_four = last(string_four);
         assert_eq!(result_four, Some((8, 1)));
 
         let string_five = "7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo";
-        let result_five = PartTwo::find_highest_index_int_from_str(string_five);
+        let result_five = last(string_five);
         assert_eq!(result_five, Some((6, 20)));
     }
 
     #[test]
     fn test_get_first_last_int_from_str() {
+        let part_two = PartTwo::new("");
+
         let string_one = "7jlncfksix7rjgrpglmn9";
-        let result_one = PartTwo::get_first_last_int_from_str(string_one);
-        assert_eq!(result_one, 79);
+        let result_one = part_two.get_first_last_int_from_str(string_one);
+        assert_eq!(result_one, Ok(79));
 
         let string_two = "vcgkgxninerqjltdbhqzzpd4nine23";
-        let result_two = PartTwo::get_first_last_int_from_str(string_two);
-        assert_eq!(result_two, 93);
+        let result_two = part_two.get_first_last_int_from_str(string_two);
+        assert_eq!(result_two, Ok(93));
 
         let string_three = "fx3";
-        let result_three = PartTwo::get_first_last_int_from_str(string_three);
-        assert_eq!(result_three, 33);
+        let result_three = part_two.get_first_last_int_from_str(string_three);
+        assert_eq!(result_three, Ok(33));
 
         let string_four = "8nrbjbpjpnineseven";
-        let result_four = PartTwo::get_first_last_int_from_str(string_four);
-        assert_eq!(result_four, 87);
+        let result_four = part_two.get_first_last_int_from_str(string_four);
+        assert_eq!(result_four, Ok(87));
 
         let string_five = "7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo";
-        let result_five = PartTwo::get_first_last_int_from_str(string_five);
-        assert_eq!(result_five, 72);
+        let result_five = part_two.get_first_last_int_from_str(string_five);
+        assert_eq!(result_five, Ok(72));
+
+        let string_six = "eightwo";
+        let result_six = part_two.get_first_last_int_from_str(string_six);
+        assert_eq!(result_six, Ok(82));
     }
 
     #[test]
     fn test_run() {
-        let calibration_values = vec![
-            "7jlncfksix7rjgrpglmn9".to_string(),
-            "vcgkgxninerqjltdbhqzzpd4nine23".to_string(),
-            "fx3".to_string(),
-            "8nrbjbpjpnineseven".to_string(),
-            "7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo".to_string(),
-        ];
-        let part_two = PartTwo::new(&calibration_values);
+        let calibration_values = "7jlncfksix7rjgrpglmn9
+vcgkgxninerqjltdbhqzzpd4nine23
+fx3
+8nrbjbpjpnineseven
+7qlfhcsnxn7fpfhjcgr6eightsevenjlpchjtzpztwo
+";
+        let part_two = PartTwo::new(calibration_values);
+        let result = part_two.run();
+        assert_eq!(result, Ok(364));
+    }
+
+    #[test]
+    fn test_run_with_vocabulary() {
+        let calibration_values = "zero1eins\nzwei3drei\n";
+        let vocabulary = [("zero", 0), ("eins", 1), ("zwei", 2), ("drei", 3)];
+        let part_two = PartTwo::with_vocabulary(calibration_values, vocabulary);
         let result = part_two.run();
-        assert_eq!(result, 364);
+        assert_eq!(result, Ok(1 + 23));
+    }
+
+    #[test]
+    fn test_run_overflow() {
+        let vocabulary = [("one", 1), ("million", 1_000_000)];
+        let part_two = PartTwo::with_vocabulary("onemillion\nmillion\n", vocabulary);
+        assert_eq!(
+            part_two.run(),
+            Err("line 2: 1000000 followed by 1000000 overflows a u32".to_string())
+        );
+
+        let vocabulary = [("zero", 0), ("billions", 2_000_000_000)];
+        let part_two =
+            PartTwo::with_vocabulary("zerobillions\nzerobillions\nzerobillions\n", vocabulary);
+        assert_eq!(
+            part_two.run(),
+            Err("line 3: the sum overflows a u32".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_crlf_and_bom() {
+        let calibration_values = "\u{feff}two1nine\r\neightwothree\r\nabcone2threexyz\r\nxtwone3four\r\n4nineeightseven2\r\nzoneight234\r\n7pqrstsixteen\r\n";
+        let part_two = PartTwo::new(calibration_values);
+        let result = part_two.run();
+        assert_eq!(result, Ok(281));
+    }
+
+    /// Tries every digit and number word at every position of a line.
+    fn reference(input: &str) -> u32 {
+        let words = [
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        ];
+        input
+            .lines()
+            .map(|line| {
+                let values: Vec<u32> = (0..line.len())
+                    .filter_map(|start| {
+                        let rest = &line[start..];
+                        let digit = rest.chars().next().and_then(|c| c.to_digit(10));
+                        let word = words.iter().position(|word| rest.starts_with(word));
+                        digit
+                            .filter(|&digit| digit > 0)
+                            .or(word.map(|index| index as u32 + 1))
+                    })
+                    .collect();
+                match (values.first(), values.last()) {
+                    (Some(first), Some(last)) => first * 10 + last,
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    /// Lines of overlapping number words, digits and a few other letters.
+    fn calibration() -> impl Strategy<Value = String> {
+        let piece = prop::sample::select(
+            &[
+                "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "1", "5",
+                "9", "e", "n", "t", "o", "x",
+            ][..],
+        );
+        let line = prop::collection::vec(piece, 0..8).prop_map(|pieces| pieces.concat());
+        prop::collection::vec(line, 1..16).prop_map(|lines| lines.join("\n"))
+    }
+
+    proptest! {
+        #[test]
+        fn run_never_panics(input in "\\PC*") {
+            let _ = PartTwo::new(&input).run();
+        }
+
+        #[test]
+        fn agrees_with_reference(input in calibration()) {
+            prop_assert_eq!(PartTwo::new(&input).run(), Ok(reference(&input)));
+        }
     }
 }
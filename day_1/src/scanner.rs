@@ -0,0 +1,245 @@
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub value: u32,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: HashMap<u8, usize>,
+    fail: usize,
+    // (token length in bytes, value) for every token ending at this node,
+    // including the ones inherited through the failure links.
+    outputs: Vec<(usize, u32)>,
+}
+
+/// Aho–Corasick automaton over a vocabulary of tokens, each mapped to the
+/// value it stands for. A line is scanned once and every token occurrence is
+/// seen, including overlapping ones such as `eightwo`.
+#[derive(Debug)]
+pub struct Scanner {
+    nodes: Vec<Node>,
+}
+
+impl Scanner {
+    pub fn new<I, S>(vocabulary: I) -> Self
+    where
+        I: IntoIterator<Item = (S, u32)>,
+        S: AsRef<str>,
+    {
+        let mut nodes = vec![Node::default()];
+        for (token, value) in vocabulary {
+            let token = token.as_ref().as_bytes();
+            if token.is_empty() {
+                continue;
+            }
+            let mut current = 0;
+            for &byte in token {
+                current = match nodes[current].children.get(&byte) {
+                    Some(&next) => next,
+                    None => {
+                        nodes.push(Node::default());
+                        let next = nodes.len() - 1;
+                        nodes[current].children.insert(byte, next);
+                        next
+                    }
+                };
+            }
+            nodes[current].outputs.push((token.len(), value));
+        }
+
+        let mut queue: VecDeque<usize> = nodes[0].children.values().copied().collect();
+        while let Some(current) = queue.pop_front() {
+            let children: Vec<(u8, usize)> = nodes[current]
+                .children
+                .iter()
+                .map(|(&byte, &child)| (byte, child))
+                .collect();
+            for (byte, child) in children {
+                let mut fail = nodes[current].fail;
+                let fail = loop {
+                    if let Some(&next) = nodes[fail].children.get(&byte) {
+                        break next;
+                    }
+                    if fail == 0 {
+                        break 0;
+                    }
+                    fail = nodes[fail].fail;
+                };
+                nodes[child].fail = fail;
+                let inherited = nodes[fail].outputs.clone();
+                nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        Self { nodes }
+    }
+
+    /// The digits `0` to `9`.
+    pub fn digits() -> Self {
+        Self::new((0..10).map(|digit| (digit.to_string(), digit)))
+    }
+
+    /// Every token occurrence in `line`, reported in order of where it ends.
+    pub fn matches<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let mut current = 0;
+        line.bytes().enumerate().flat_map(move |(index, byte)| {
+            current = self.step(current, byte);
+            self.nodes[current]
+                .outputs
+                .iter()
+                .map(move |&(length, value)| Match {
+                    value,
+                    start: index + 1 - length,
+                    end: index + 1,
+                })
+        })
+    }
+
+    fn step(&self, mut current: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[current].children.get(&byte) {
+                return next;
+            }
+            if current == 0 {
+                return 0;
+            }
+            current = self.nodes[current].fail;
+        }
+    }
+
+    /// The earliest and the latest starting token in `line`. When two tokens
+    /// start at the same position the longer one wins.
+    pub fn first_last(&self, line: &str) -> Option<(Match, Match)> {
+        self.matches(line).fold(None, |found, m| match found {
+            None => Some((m, m)),
+            Some((first, last)) => {
+                let first = if (m.start, std::cmp::Reverse(m.end))
+                    < (first.start, std::cmp::Reverse(first.end))
+                {
+                    m
+                } else {
+                    first
+                };
+                let last = if (m.start, m.end) > (last.start, last.end) {
+                    m
+                } else {
+                    last
+                };
+                Some((first, last))
+            }
+        })
+    }
+
+    /// Joins the first and last token values as if their decimal digits were
+    /// written next to each other, or `0` when the line holds no token. Fails
+    /// when the joined value does not fit in a `u32`.
+    pub fn calibration_value(&self, line: &str) -> Result<u32, String> {
+        match self.first_last(line) {
+            Some((first, last)) => concatenate(first.value, last.value).ok_or_else(|| {
+                format!("{} followed by {} overflows a u32", first.value, last.value)
+            }),
+            None => Ok(0),
+        }
+    }
+}
+
+fn concatenate(first: u32, last: u32) -> Option<u32> {
+    let mut shift: u64 = 10;
+    while shift <= u64::from(last) {
+        shift *= 10;
+    }
+    let value = u64::from(first)
+        .checked_mul(shift)?
+        .checked_add(u64::from(last))?;
+    u32::try_from(value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digits() {
+        let scanner = Scanner::digits();
+        assert_eq!(scanner.calibration_value("1abc2"), Ok(12));
+        assert_eq!(scanner.calibration_value("pqr3stu8vwx"), Ok(38));
+        assert_eq!(scanner.calibration_value("a1b2c3d4e5f"), Ok(15));
+        assert_eq!(scanner.calibration_value("treb7uchet"), Ok(77));
+        assert_eq!(scanner.calibration_value("trebuchet"), Ok(0));
+    }
+
+    #[test]
+    fn test_overlapping_tokens() {
+        let scanner = Scanner::new([("one", 1), ("eight", 8), ("two", 2), ("three", 3)]);
+        let (first, last) = scanner.first_last("eightwothree").unwrap();
+        assert_eq!(
+            first,
+            Match {
+                value: 8,
+                start: 0,
+                end: 5
+            }
+        );
+        assert_eq!(
+            last,
+            Match {
+                value: 3,
+                start: 7,
+                end: 12
+            }
+        );
+
+        let (first, last) = scanner.first_last("xtwone").unwrap();
+        assert_eq!(first.value, 2);
+        assert_eq!(last.value, 1);
+    }
+
+    #[test]
+    fn test_token_nested_in_longer_token() {
+        let scanner = Scanner::new([("abcd", 1), ("bc", 2), ("c", 3)]);
+        let (first, last) = scanner.first_last("abcd").unwrap();
+        assert_eq!(first.value, 1);
+        assert_eq!(last.value, 3);
+    }
+
+    #[test]
+    fn test_custom_vocabulary() {
+        let scanner = Scanner::new([
+            ("zero", 0),
+            ("un", 1),
+            ("deux", 2),
+            ("dix", 10),
+            ("douze", 12),
+        ]);
+        assert_eq!(scanner.calibration_value("xdeuxyzero"), Ok(20));
+        assert_eq!(scanner.calibration_value("zeroun"), Ok(1));
+        assert_eq!(scanner.calibration_value("dixdouze"), Ok(1012));
+        assert_eq!(scanner.calibration_value("douzedeux"), Ok(122));
+
+        let scanner = Scanner::new([("drei", 3), ("zwölf", 12)]);
+        assert_eq!(scanner.calibration_value("zwölfxdrei"), Ok(123));
+    }
+
+    #[test]
+    fn test_large_values() {
+        let scanner = Scanner::new([("zero", 0), ("two", 2), ("million", 1_000_000)]);
+        assert_eq!(scanner.calibration_value("twomillion"), Ok(21_000_000));
+        assert_eq!(scanner.calibration_value("zeromillion"), Ok(1_000_000));
+        assert_eq!(
+            scanner.calibration_value("million"),
+            Err("1000000 followed by 1000000 overflows a u32".to_string())
+        );
+
+        let scanner = Scanner::new([("one", 1), ("max", u32::MAX)]);
+        assert_eq!(
+            scanner.calibration_value("maxone"),
+            Err(format!("{} followed by 1 overflows a u32", u32::MAX))
+        );
+        assert!(scanner.calibration_value("onemax").is_err());
+    }
+}
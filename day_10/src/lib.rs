@@ -1,2 +1,230 @@
 pub mod part_one;
 pub mod part_two;
+
+/// The rows of a pipe sketch: only `|-LJ7F.` tiles and a single `S`, with
+/// every row as wide as the first.
+#[tracing::instrument(name = "parse", skip_all)]
+pub fn parse_sketch(input: &str) -> Result<Vec<&str>, String> {
+    let rows: Vec<&str> = input.trim_end().lines().collect();
+    let width = rows.first().map_or(0, |row| row.len());
+    if width == 0 {
+        return Err("Empty sketch".to_string());
+    }
+    let mut starts = 0;
+    for (y, row) in rows.iter().enumerate() {
+        if let Some(c) = row.chars().find(|c| !"|-LJ7F.S".contains(*c)) {
+            return Err(format!("Invalid tile {:?} in row {}", c, y));
+        }
+        if row.len() != width {
+            return Err(format!("Row {} is not as wide as the first", y));
+        }
+        starts += row.matches('S').count();
+    }
+    if starts != 1 {
+        return Err(format!("Expected one S, found {}", starts));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// A generated sketch, with what the loop in it is known to measure.
+    #[derive(Clone, Debug)]
+    pub(crate) struct Sketch {
+        pub input: String,
+        pub loop_length: usize,
+        pub enclosed: usize,
+    }
+
+    /// Draws the outline of a random blob of cells as the loop: the pipes sit
+    /// on the corners of the cells, so the tiles enclosed are exactly the
+    /// corners with all four cells around them in the blob. Blobs with holes
+    /// or touching themselves diagonally are redrawn. The other tiles are
+    /// junk pipe, none of which connects to `S`.
+    pub(crate) fn sketches() -> impl Strategy<Value = Sketch> {
+        (2..=5usize, 2..=5usize)
+            .prop_flat_map(|(width, height)| {
+                (
+                    Just((width, height)),
+                    prop::collection::vec(any::<bool>(), width * height),
+                    0..width * height,
+                    any::<prop::sample::Index>(),
+                    prop::collection::vec(prop::sample::select(&b"|-LJ7F..."[..]), 64),
+                )
+            })
+            .prop_filter_map(
+                "blob with a hole or a diagonal pinch",
+                |((width, height), cells, seed, start, junk)| {
+                    sketch(width, height, &cells, seed, start, &junk)
+                },
+            )
+    }
+
+    fn sketch(
+        width: usize,
+        height: usize,
+        cells: &[bool],
+        seed: usize,
+        start: prop::sample::Index,
+        junk: &[u8],
+    ) -> Option<Sketch> {
+        // The blob is the cells reachable from `seed` through other cells.
+        let neighbours = |cell: usize| {
+            let (x, y) = (cell % width, cell / width);
+            let mut neighbours = Vec::new();
+            if x > 0 {
+                neighbours.push(cell - 1);
+            }
+            if x + 1 < width {
+                neighbours.push(cell + 1);
+            }
+            if y > 0 {
+                neighbours.push(cell - width);
+            }
+            if y + 1 < height {
+                neighbours.push(cell + width);
+            }
+            neighbours
+        };
+        let mut blob = vec![false; cells.len()];
+        let mut stack = vec![seed];
+        blob[seed] = true;
+        while let Some(cell) = stack.pop() {
+            for next in neighbours(cell) {
+                if cells[next] && !blob[next] {
+                    blob[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        // Every cell outside the blob must reach the border, or it is a hole.
+        let mut outside = vec![false; cells.len()];
+        let mut stack: Vec<usize> = (0..cells.len())
+            .filter(|&cell| {
+                let (x, y) = (cell % width, cell / width);
+                !blob[cell] && (x == 0 || y == 0 || x + 1 == width || y + 1 == height)
+            })
+            .collect();
+        for &cell in &stack {
+            outside[cell] = true;
+        }
+        while let Some(cell) = stack.pop() {
+            for next in neighbours(cell) {
+                if !blob[next] && !outside[next] {
+                    outside[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        if (0..cells.len()).any(|cell| !blob[cell] && !outside[cell]) {
+            return None;
+        }
+
+        let inside = |x: isize, y: isize| {
+            (0..width as isize).contains(&x)
+                && (0..height as isize).contains(&y)
+                && blob[y as usize * width + x as usize]
+        };
+        // Tiles are the corners of the cells, with a border of ground around.
+        let mut rows = vec![vec![b'.'; width + 3]; height + 3];
+        let mut corners = Vec::new();
+        let mut enclosed = 0;
+        for y in 0..=height as isize {
+            for x in 0..=width as isize {
+                let around = [
+                    inside(x - 1, y - 1),
+                    inside(x, y - 1),
+                    inside(x - 1, y),
+                    inside(x, y),
+                ];
+                if around.iter().all(|&cell| cell) {
+                    enclosed += 1;
+                }
+                let up = around[0] != around[1];
+                let down = around[2] != around[3];
+                let left = around[0] != around[2];
+                let right = around[1] != around[3];
+                let tile = match (up, down, left, right) {
+                    (false, false, false, false) => continue,
+                    (true, true, false, false) => b'|',
+                    (false, false, true, true) => b'-',
+                    (true, false, false, true) => b'L',
+                    (true, false, true, false) => b'J',
+                    (false, true, true, false) => b'7',
+                    (false, true, false, true) => b'F',
+                    _ => return None,
+                };
+                rows[y as usize + 1][x as usize + 1] = tile;
+                corners.push((x as usize + 1, y as usize + 1));
+            }
+        }
+
+        let (start_x, start_y) = *start.get(&corners);
+        let mut junk = junk.iter().cycle();
+        for (y, row) in rows.iter_mut().enumerate() {
+            for (x, tile) in row.iter_mut().enumerate() {
+                if *tile != b'.' {
+                    continue;
+                }
+                let towards_start: &[u8] =
+                    match (x as isize - start_x as isize, y as isize - start_y as isize) {
+                        (-1, 0) => b"-LF",
+                        (1, 0) => b"-J7",
+                        (0, -1) => b"|7F",
+                        (0, 1) => b"|LJ",
+                        _ => b"",
+                    };
+                let pipe = *junk.next().unwrap();
+                if !towards_start.contains(&pipe) {
+                    *tile = pipe;
+                }
+            }
+        }
+        rows[start_y][start_x] = b'S';
+
+        let input = rows
+            .iter()
+            .map(|row| String::from_utf8(row.clone()).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(Sketch {
+            input,
+            loop_length: corners.len(),
+            enclosed,
+        })
+    }
+
+    #[test]
+    fn invalid_sketches() {
+        assert_eq!(
+            parse_sketch("S-7\n|.|\nL-J\n"),
+            Ok(vec!["S-7", "|.|", "L-J"])
+        );
+        assert!(parse_sketch("").is_err());
+        assert!(parse_sketch("F-7\n|.|\nL-J").is_err());
+        assert!(parse_sketch("S-S\n|.|\nL-J").is_err());
+        assert!(parse_sketch("S-7\n|.\nL-J").is_err());
+        assert!(parse_sketch("S-7\n|x|\nL-J").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = parse_sketch(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "[|\\-LJ7F.S\n]{0,32}") {
+            let _ = parse_sketch(&input);
+        }
+
+        #[test]
+        fn sketches_parse(sketch in sketches()) {
+            prop_assert!(parse_sketch(&sketch.input).is_ok());
+        }
+    }
+}
@@ -94,14 +94,15 @@ Here are the distances for each tile on that loop:
 Find the single giant loop starting at S. How many steps along the loop does it take to get from the starting position to the point farthest from the starting position?
 */
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use crate::parse_sketch;
 
 #[tracing::instrument(skip(input))]
 pub fn run(input: &str) -> i32 {
-    let contents = input.to_string();
-    let grid: Vec<Vec<char>> = contents
-        .trim()
-        .split('\n')
+    let grid: Vec<Vec<char>> = parse_sketch(input)
+        .unwrap()
+        .iter()
         .map(|line| line.chars().collect())
         .collect();
 
@@ -122,11 +123,13 @@ pub fn run(input: &str) -> i32 {
     while let Some((x, y)) = queue.pop_front() {
         poly.push((x, y));
         let distance = distances[&(x, y)];
+        // Stepping off the top or left edge wraps around to a coordinate the
+        // bounds check below rejects.
         let neighbors = [
             (x + 1, y, "-LFS", "-J7"),
-            (x - 1, y, "-J7S", "-LF"),
+            (x.wrapping_sub(1), y, "-J7S", "-LF"),
             (x, y + 1, "|F7S", "|LJ"),
-            (x, y - 1, "|LJS", "|F7"),
+            (x, y.wrapping_sub(1), "|LJS", "|F7"),
         ];
 
         for &(u, v, f, g) in &neighbors {
@@ -135,9 +138,9 @@ pub fn run(input: &str) -> i32 {
                 && f.contains(grid[y][x])
                 && g.contains(grid[v][u])
             {
-                if !distances.contains_key(&(u, v)) {
+                if let Entry::Vacant(entry) = distances.entry((u, v)) {
+                    entry.insert(distance + 1);
                     queue.push_back((u, v));
-                    distances.insert((u, v), distance + 1);
                 }
             }
         }
@@ -148,6 +151,9 @@ pub fn run(input: &str) -> i32 {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
+    use crate::tests::sketches;
 
     use super::*;
 
@@ -157,4 +163,18 @@ mod tests {
         let output = run(input);
         assert_eq!(output, 4);
     }
+
+    #[test]
+    fn start_on_the_edge() {
+        assert_eq!(run("S"), 0);
+        assert_eq!(run("S7\nLJ"), 2);
+        assert_eq!(run(".F7\nFJ|\nS-J"), 4);
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(sketch in sketches()) {
+            prop_assert_eq!(run(&sketch.input) as usize, sketch.loop_length / 2);
+        }
+    }
 }
@@ -94,12 +94,11 @@ Figure out whether you have time to search for the nest by calculating the area
 
 use std::collections::{HashMap, VecDeque};
 
+use crate::parse_sketch;
+
 #[tracing::instrument(skip(input))]
 pub fn run(input: &str) -> i32 {
-    let mut m = Vec::new();
-    for line in input.lines() {
-        m.push(line);
-    }
+    let m = parse_sketch(input).unwrap();
 
     let n = HashMap::from([
         ('|', vec![(0, -1), (0, 1)]),
@@ -123,6 +122,28 @@ pub fn run(input: &str) -> i32 {
 
     assert!(found);
 
+    // The pipe under S, from which of its neighbours connect to it. Only
+    // whether it is an L or a 7 matters when counting crossings below.
+    let connects = |dx: isize, dy: isize, pipes: &str| {
+        let x = start.0 as isize + dx;
+        let y = start.1 as isize + dy;
+        x >= 0
+            && y >= 0
+            && m.get(y as usize)
+                .and_then(|line| line.chars().nth(x as usize))
+                .is_some_and(|c| pipes.contains(c))
+    };
+    let start_pipe = match (
+        connects(0, -1, "|7F"),
+        connects(0, 1, "|LJ"),
+        connects(-1, 0, "-LF"),
+        connects(1, 0, "-J7"),
+    ) {
+        (true, false, false, true) => 'L',
+        (false, true, true, false) => '7',
+        _ => 'S',
+    };
+
     let mut q = VecDeque::new();
     let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
 
@@ -135,7 +156,13 @@ pub fn run(input: &str) -> i32 {
                 .get(new_y as usize)
                 .and_then(|line| line.chars().nth(new_x as usize))
             {
-                if let Some(dirs) = n.get(&c) {
+                // Only pipes leading back to S are part of the loop.
+                let dirs = n.get(&c).filter(|dirs| {
+                    dirs.iter().any(|&(dx2, dy2)| {
+                        (new_x + dx2, new_y + dy2) == (start.0 as isize, start.1 as isize)
+                    })
+                });
+                if let Some(dirs) = dirs {
                     for &(dx2, dy2) in dirs {
                         let next_x = new_x + dx2;
                         let next_y = new_y + dy2;
@@ -151,13 +178,17 @@ pub fn run(input: &str) -> i32 {
     let mut dists = HashMap::from([(start, 0)]);
 
     while let Some((d, (x, y))) = q.pop_front() {
+        // Pipes on the edge may lead off the sketch.
+        let Some(c) = m.get(y).and_then(|line| line.chars().nth(x)) else {
+            continue;
+        };
         if dists.contains_key(&(x, y)) {
             continue;
         }
 
         dists.insert((x, y), d);
 
-        if let Some(dirs) = n.get(&m[y].chars().nth(x).unwrap()) {
+        if let Some(dirs) = n.get(&c) {
             for &(dx, dy) in dirs {
                 let new_x = x as isize + dx;
                 let new_y = y as isize + dy;
@@ -181,7 +212,11 @@ pub fn run(input: &str) -> i32 {
             let (mut x2, mut y2) = (x, y);
 
             while x2 < w && y2 < h {
-                let c2 = m[y2].chars().nth(x2).unwrap();
+                let c2 = if (x2, y2) == start {
+                    start_pipe
+                } else {
+                    m[y2].chars().nth(x2).unwrap()
+                };
                 if dists.contains_key(&(x2, y2)) && c2 != 'L' && c2 != '7' {
                     crosses += 1;
                 }
@@ -200,13 +235,44 @@ pub fn run(input: &str) -> i32 {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
+    use crate::tests::sketches;
 
     use super::*;
 
     #[test]
     fn part_2() {
-        let input = include_str!("../test_data.txt");
+        let input = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
         let output = run(input);
         assert_eq!(output, 4);
     }
+
+    #[test]
+    fn start_on_a_corner() {
+        let input = ".....\n.F-S.\n.|.|.\n.L-J.\n.....";
+        assert_eq!(run(input), 1);
+    }
+
+    #[test]
+    fn pipes_leading_off_the_sketch() {
+        assert_eq!(run("S-"), 0);
+        assert_eq!(run("-S-\n|.|"), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(sketch in sketches()) {
+            prop_assert_eq!(run(&sketch.input) as usize, sketch.enclosed);
+        }
+    }
 }
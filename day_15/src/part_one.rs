@@ -64,7 +64,6 @@ In this example, the sum of these results is 1320. Unfortunately, the reindeer h
 Run the HASH algorithm on each step in the initialization sequence. What is the sum of the results? (The initialization sequence is one long line; be careful when copy-pasting it.)
 */
 
-#[tracing::instrument(skip(input))]
 fn hash(input: &str) -> i32 {
     let mut current_value = 0;
     for c in input.chars() {
@@ -76,7 +75,6 @@ fn hash(input: &str) -> i32 {
     current_value
 }
 
-#[tracing::instrument(skip(input))]
 fn hash_line(input: &str) -> i32 {
     input.split(',').map(|s| s.trim()).map(hash).sum::<i32>()
 }
@@ -88,6 +86,7 @@ pub fn run(input: &str) -> i32 {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -98,4 +97,20 @@ mod tests {
         assert_eq!(output, 1320);
         assert_eq!(hash("HASH"), 52);
     }
+
+    proptest! {
+        #[test]
+        fn run_never_panics(input in "\\PC*") {
+            run(&input);
+        }
+
+        #[test]
+        fn agrees_with_reference(input in "[a-z]{1,6}([=-][0-9]?)?(,[a-z]{1,6}([=-][0-9]?)?){0,16}") {
+            let expected: i32 = input
+                .split(',')
+                .map(|step| step.bytes().fold(0, |h, c| (h + c as i32) * 17 % 256))
+                .sum();
+            prop_assert_eq!(run(&input), expected);
+        }
+    }
 }
@@ -90,7 +90,7 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{alpha1, digit1},
-    combinator::map,
+    combinator::{map, map_res},
     multi::separated_list1,
     sequence::preceded,
     IResult,
@@ -102,26 +102,36 @@ enum Operation {
     Add(usize),
 }
 
+/// One comma separated step of the initialization sequence, such as `rn=1`
+/// or `cm-`.
 #[derive(Debug)]
-struct Step<'a> {
+pub struct Step<'a> {
     label: &'a str,
     operation: Operation,
 }
 
 impl<'a> Step<'a> {
-    fn parse(input: &'a str) -> IResult<&str, Self> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
         let (input, label) = alpha1(input)?;
         let (input, operation) = alt((
             map(tag("-"), |_| Operation::Remove),
-            map(preceded(tag("="), digit1), |s: &str| {
-                Operation::Add(s.parse().unwrap())
+            map_res(preceded(tag("="), digit1), |s: &str| {
+                s.parse().map(Operation::Add)
             }),
         ))(input)?;
         Ok((input, Self::new(label, operation)))
     }
 
-    fn parse_all(input: &'a str) -> Vec<Self> {
-        separated_list1(tag(","), Step::parse)(input).unwrap().1
+    /// Parses the whole sequence, ignoring the trailing newline. Anything
+    /// that is not a step is an error rather than silently dropped.
+    #[tracing::instrument(name = "parse", skip_all)]
+    pub fn parse_all(input: &'a str) -> Result<Vec<Self>, String> {
+        let input = input.trim_end();
+        match separated_list1(tag(","), Step::parse)(input) {
+            Ok(("", steps)) => Ok(steps),
+            Ok((rest, _)) => Err(format!("Invalid step at {:?}", rest)),
+            Err(_) => Err(format!("Invalid step at {:?}", input)),
+        }
     }
 
     fn new(label: &'a str, operation: Operation) -> Self {
@@ -213,15 +223,16 @@ impl<'a> Boxes<'a> {
 
 #[tracing::instrument(skip(input))]
 pub fn run(input: &str) -> usize {
-    let steps = Step::parse_all(input);
+    let steps = Step::parse_all(input).unwrap();
     let mut boxes = Boxes::new();
     boxes.perform_steps(&steps);
-    let p2 = boxes.focusing_power();
-    p2
+    boxes.focusing_power()
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -230,4 +241,72 @@ mod tests {
         let output = run(input);
         assert_eq!(output, 145);
     }
+
+    #[test]
+    fn invalid_steps() {
+        assert!(Step::parse_all("rn=1,cm-\n").is_ok());
+        assert!(Step::parse_all("").is_err());
+        assert!(Step::parse_all("rn=1,,cm-").is_err());
+        assert!(Step::parse_all("rn=").is_err());
+        assert!(Step::parse_all("rn=99999999999999999999999").is_err());
+    }
+
+    /// Keeps every lens in one list in the order it was inserted, and only
+    /// works out the boxes when computing the focusing power.
+    fn reference(input: &str) -> usize {
+        let mut lenses: Vec<(&str, usize)> = Vec::new();
+        for step in input.trim_end().split(',') {
+            if let Some(label) = step.strip_suffix('-') {
+                lenses.retain(|&(other, _)| other != label);
+            } else {
+                let (label, focal_length) = step.split_once('=').unwrap();
+                let focal_length = focal_length.parse().unwrap();
+                match lenses.iter_mut().find(|(other, _)| *other == label) {
+                    Some(lens) => lens.1 = focal_length,
+                    None => lenses.push((label, focal_length)),
+                }
+            }
+        }
+        let hash = |label: &str| label.bytes().fold(0, |h, c| (h + c as usize) * 17 % 256);
+        (0..256)
+            .map(|box_number| {
+                lenses
+                    .iter()
+                    .filter(|(label, _)| hash(label) == box_number)
+                    .enumerate()
+                    .map(|(slot, (_, focal_length))| (box_number + 1) * (slot + 1) * focal_length)
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Few, short labels so that steps keep hitting the same lenses.
+    fn sequence() -> impl Strategy<Value = String> {
+        let step =
+            ("[a-d]{1,2}", prop::option::of(1..=9usize)).prop_map(|(label, focal_length)| {
+                match focal_length {
+                    Some(focal_length) => format!("{}={}", label, focal_length),
+                    None => format!("{}-", label),
+                }
+            });
+        prop::collection::vec(step, 1..64).prop_map(|steps| steps.join(","))
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = Step::parse_all(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "[a-z=,\\-0-9\n]{0,32}") {
+            let _ = Step::parse_all(&input);
+        }
+
+        #[test]
+        fn agrees_with_reference(input in sequence()) {
+            prop_assert_eq!(Step::parse_all(&input).unwrap().len(), input.split(',').count());
+            prop_assert_eq!(run(&input), reference(&input));
+        }
+    }
 }
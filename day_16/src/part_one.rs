@@ -81,8 +81,9 @@ struct Tile {
 
 type Position = (i32, i32);
 
+/// The contraption: one row of tiles per line, all of the same width.
 #[derive(Debug)]
-struct Grid {
+pub struct Grid {
     tiles: Vec<Vec<Tile>>,
 }
 
@@ -179,28 +180,41 @@ impl Grid {
 }
 
 impl FromStr for Grid {
-    type Err = ();
+    type Err = String;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut tiles = Vec::new();
-        for line in s.lines() {
+        for (y, line) in s.lines().enumerate() {
             let mut row = Vec::new();
-            for c in line.chars() {
+            for (x, c) in line.chars().enumerate() {
                 let position_type = match c {
                     '.' => PositionType::Empty,
                     '/' => PositionType::UpwardMirror,
                     '\\' => PositionType::DownwardMirror,
                     '|' => PositionType::Splitter(SplitterType::Vertical),
                     '-' => PositionType::Splitter(SplitterType::Horizontal),
-                    _ => panic!("Invalid character"),
+                    _ => return Err(format!("Invalid character {:?} at {}, {}", c, x, y)),
                 };
                 row.push(Tile {
                     position_type,
                     energized: false,
                 });
             }
+            if row.is_empty() {
+                return Err(format!("Row {} is empty", y));
+            }
+            if tiles
+                .first()
+                .is_some_and(|first: &Vec<Tile>| first.len() != row.len())
+            {
+                return Err(format!("Row {} is not as wide as the first", y));
+            }
             tiles.push(row);
         }
+        if tiles.is_empty() {
+            return Err("Empty grid".to_string());
+        }
         Ok(Grid { tiles })
     }
 }
@@ -222,6 +236,7 @@ pub fn run(input: &str) -> i32 {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -231,4 +246,73 @@ mod tests {
         let output = run(input);
         assert_eq!(output, 46);
     }
+
+    #[test]
+    fn invalid_grids() {
+        assert!(Grid::from_str("./\n|-\n").is_ok());
+        assert!(Grid::from_str("").is_err());
+        assert!(Grid::from_str("./\n|\n").is_err());
+        assert!(Grid::from_str("./\n\n").is_err());
+        assert!(Grid::from_str(".x\n").is_err());
+    }
+
+    /// Follows every beam with an explicit stack instead of recursion, over
+    /// the characters of the input rather than the parsed tiles.
+    fn energized(rows: &[Vec<char>], start: (i32, i32), direction: (i32, i32)) -> usize {
+        let (height, width) = (rows.len() as i32, rows[0].len() as i32);
+        let mut seen = HashSet::new();
+        let mut beams = vec![(start, direction)];
+        while let Some(((x, y), (dx, dy))) = beams.pop() {
+            if x < 0 || y < 0 || x >= width || y >= height || !seen.insert(((x, y), (dx, dy))) {
+                continue;
+            }
+            let directions = match (rows[y as usize][x as usize], dx) {
+                ('/', _) => vec![(-dy, -dx)],
+                ('\\', _) => vec![(dy, dx)],
+                ('|', 0) | ('-', 1 | -1) | ('.', _) => vec![(dx, dy)],
+                ('|', _) => vec![(0, -1), (0, 1)],
+                _ => vec![(-1, 0), (1, 0)],
+            };
+            for (dx, dy) in directions {
+                beams.push(((x + dx, y + dy), (dx, dy)));
+            }
+        }
+        seen.into_iter()
+            .map(|(position, _)| position)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    fn grid() -> impl Strategy<Value = String> {
+        let tile = prop::sample::select(vec!['.', '.', '.', '.', '/', '\\', '|', '-']);
+        (1..8usize, 1..8usize).prop_flat_map(move |(width, height)| {
+            prop::collection::vec(prop::collection::vec(tile.clone(), width), height).prop_map(
+                |rows| {
+                    rows.iter()
+                        .map(|row| row.iter().collect::<String>() + "\n")
+                        .collect()
+                },
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = Grid::from_str(&input);
+        }
+
+        #[test]
+        fn parsed_grids_never_panic(input in "[./\\\\|\\-x\n]{0,40}") {
+            if Grid::from_str(&input).is_ok() {
+                run(&input);
+            }
+        }
+
+        #[test]
+        fn agrees_with_reference(input in grid()) {
+            let rows: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+            prop_assert_eq!(run(&input) as usize, energized(&rows, (0, 0), (1, 0)));
+        }
+    }
 }
@@ -55,12 +55,14 @@ struct Tile {
 
 type Position = (i32, i32);
 
+/// The contraption: one row of tiles per line, all of the same width.
 #[derive(Debug)]
-struct Grid {
+pub struct Grid {
     tiles: Vec<Vec<Tile>>,
 }
 
 impl Grid {
+    #[tracing::instrument(skip_all)]
     fn find_max_energized_tiles(&mut self) -> (Position, i32) {
         let mut max_energized_tiles = 0;
         let mut max_energized_tiles_position = (0, 0);
@@ -70,44 +72,49 @@ impl Grid {
         // Iterate over all possible starting edges
         for y in 0..height {
             for x in 0..width {
-                // Skip non-edge tiles
-                if y != 0 && y != height - 1 && x != 0 && x != width - 1 {
-                    continue;
-                }
-
-                let starting_position = (x as i32, y as i32);
-                let starting_direction = self.get_starting_direction(x, y, width, height);
-                let mut visited_positions = HashSet::new();
-                self.parse_beam(
-                    starting_position,
-                    starting_direction,
-                    &mut visited_positions,
-                );
-                let energized_tiles = self.get_energized_tiles();
-                if energized_tiles.len() > max_energized_tiles {
-                    max_energized_tiles = energized_tiles.len();
-                    max_energized_tiles_position = starting_position;
+                for starting_direction in self.get_starting_directions(x, y, width, height) {
+                    let starting_position = (x as i32, y as i32);
+                    let mut visited_positions = HashSet::new();
+                    self.parse_beam(
+                        starting_position,
+                        starting_direction,
+                        &mut visited_positions,
+                    );
+                    let energized_tiles = self.get_energized_tiles();
+                    if energized_tiles.len() > max_energized_tiles {
+                        max_energized_tiles = energized_tiles.len();
+                        max_energized_tiles_position = starting_position;
+                    }
+                    self.reset_tiles();
                 }
-                self.reset_tiles();
             }
         }
         (max_energized_tiles_position, max_energized_tiles as i32)
     }
 
-    fn get_starting_direction(
+    /// Every direction heading away from an edge the tile is on: none for
+    /// inner tiles, two for corners.
+    fn get_starting_directions(
         &self,
         x: usize,
         y: usize,
         width: usize,
         height: usize,
-    ) -> (i32, i32) {
-        match (x, y) {
-            (0, _) => (1, 0),                     // Left edge, move right
-            (_, 0) => (0, 1),                     // Top edge, move down
-            (x, _) if x == width - 1 => (-1, 0),  // Right edge, move left
-            (_, y) if y == height - 1 => (0, -1), // Bottom edge, move up
-            _ => unreachable!(),
+    ) -> Vec<(i32, i32)> {
+        let mut directions = Vec::new();
+        if x == 0 {
+            directions.push((1, 0)); // Left edge, move right
+        }
+        if y == 0 {
+            directions.push((0, 1)); // Top edge, move down
         }
+        if x == width - 1 {
+            directions.push((-1, 0)); // Right edge, move left
+        }
+        if y == height - 1 {
+            directions.push((0, -1)); // Bottom edge, move up
+        }
+        directions
     }
 
     fn reset_tiles(&mut self) {
@@ -210,28 +217,41 @@ impl Grid {
 }
 
 impl FromStr for Grid {
-    type Err = ();
+    type Err = String;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut tiles = Vec::new();
-        for line in s.lines() {
+        for (y, line) in s.lines().enumerate() {
             let mut row = Vec::new();
-            for c in line.chars() {
+            for (x, c) in line.chars().enumerate() {
                 let position_type = match c {
                     '.' => PositionType::Empty,
                     '/' => PositionType::UpwardMirror,
                     '\\' => PositionType::DownwardMirror,
                     '|' => PositionType::Splitter(SplitterType::Vertical),
                     '-' => PositionType::Splitter(SplitterType::Horizontal),
-                    _ => panic!("Invalid character"),
+                    _ => return Err(format!("Invalid character {:?} at {}, {}", c, x, y)),
                 };
                 row.push(Tile {
                     position_type,
                     energized: false,
                 });
             }
+            if row.is_empty() {
+                return Err(format!("Row {} is empty", y));
+            }
+            if tiles
+                .first()
+                .is_some_and(|first: &Vec<Tile>| first.len() != row.len())
+            {
+                return Err(format!("Row {} is not as wide as the first", y));
+            }
             tiles.push(row);
         }
+        if tiles.is_empty() {
+            return Err("Empty grid".to_string());
+        }
         Ok(Grid { tiles })
     }
 }
@@ -245,6 +265,7 @@ pub fn run(input: &str) -> i32 {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -254,4 +275,81 @@ mod tests {
         let output = run(input);
         assert_eq!(output, 51);
     }
+
+    #[test]
+    fn invalid_grids() {
+        assert!(Grid::from_str("./\n|-\n").is_ok());
+        assert!(Grid::from_str("").is_err());
+        assert!(Grid::from_str("./\n|\n").is_err());
+        assert!(Grid::from_str("./\n\n").is_err());
+        assert!(Grid::from_str(".x\n").is_err());
+    }
+
+    /// Follows every beam with an explicit stack instead of recursion, over
+    /// the characters of the input rather than the parsed tiles.
+    fn energized(rows: &[Vec<char>], start: (i32, i32), direction: (i32, i32)) -> usize {
+        let (height, width) = (rows.len() as i32, rows[0].len() as i32);
+        let mut seen = HashSet::new();
+        let mut beams = vec![(start, direction)];
+        while let Some(((x, y), (dx, dy))) = beams.pop() {
+            if x < 0 || y < 0 || x >= width || y >= height || !seen.insert(((x, y), (dx, dy))) {
+                continue;
+            }
+            let directions = match (rows[y as usize][x as usize], dx) {
+                ('/', _) => vec![(-dy, -dx)],
+                ('\\', _) => vec![(dy, dx)],
+                ('|', 0) | ('-', 1 | -1) | ('.', _) => vec![(dx, dy)],
+                ('|', _) => vec![(0, -1), (0, 1)],
+                _ => vec![(-1, 0), (1, 0)],
+            };
+            for (dx, dy) in directions {
+                beams.push(((x + dx, y + dy), (dx, dy)));
+            }
+        }
+        seen.into_iter()
+            .map(|(position, _)| position)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    fn grid() -> impl Strategy<Value = String> {
+        let tile = prop::sample::select(vec!['.', '.', '.', '.', '/', '\\', '|', '-']);
+        (1..8usize, 1..8usize).prop_flat_map(move |(width, height)| {
+            prop::collection::vec(prop::collection::vec(tile.clone(), width), height).prop_map(
+                |rows| {
+                    rows.iter()
+                        .map(|row| row.iter().collect::<String>() + "\n")
+                        .collect()
+                },
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = Grid::from_str(&input);
+        }
+
+        #[test]
+        fn parsed_grids_never_panic(input in "[./\\\\|\\-x\n]{0,40}") {
+            if Grid::from_str(&input).is_ok() {
+                run(&input);
+            }
+        }
+
+        #[test]
+        fn agrees_with_reference(input in grid()) {
+            let rows: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+            let (width, height) = (rows[0].len() as i32, rows.len() as i32);
+            let entries = (0..width)
+                .flat_map(|x| [((x, 0), (0, 1)), ((x, height - 1), (0, -1))])
+                .chain((0..height).flat_map(|y| [((0, y), (1, 0)), ((width - 1, y), (-1, 0))]));
+            let expected = entries
+                .map(|(start, direction)| energized(&rows, start, direction))
+                .max()
+                .unwrap();
+            prop_assert_eq!(run(&input) as usize, expected);
+        }
+    }
 }
@@ -64,8 +64,9 @@ struct Position {
     move_count: usize, // Count of moves in the current direction
 }
 
+/// The heat loss of every city block, one row of digits per line.
 #[derive(Debug)]
-struct Grid {
+pub struct Grid {
     tiles: Vec<Vec<u8>>,
 }
 
@@ -74,7 +75,7 @@ impl Grid {
         Self { tiles }
     }
 
-    pub fn neighbors(&self, pos: &Position) -> Vec<(Position, u32)> {
+    fn neighbors(&self, pos: &Position) -> Vec<(Position, u32)> {
         let mut neighbors = Vec::new();
         let directions = [(0, 1), (1, 0), (0, -1), (-1, 0)]; // Right, Down, Left, Up
 
@@ -111,6 +112,7 @@ impl Grid {
         neighbors
     }
 
+    #[tracing::instrument(skip_all)]
     pub fn shortest_path(&self) -> u32 {
         let start = Position {
             x: 0,
@@ -140,21 +142,35 @@ impl Grid {
 }
 
 impl FromStr for Grid {
-    type Err = ();
+    type Err = String;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let tiles = s
             .lines()
-            .map(|line| {
+            .enumerate()
+            .map(|(y, line)| {
                 line.chars()
-                    .map(|c| c.to_digit(10).unwrap() as u8)
-                    .collect()
+                    .map(|c| {
+                        c.to_digit(10)
+                            .map(|digit| digit as u8)
+                            .ok_or_else(|| format!("Invalid heat loss {:?} in row {}", c, y))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
+        let width = tiles.first().map_or(0, Vec::len);
+        if width == 0 {
+            return Err("Empty grid".to_string());
+        }
+        if let Some(y) = tiles.iter().position(|row| row.len() != width) {
+            return Err(format!("Row {} is not as wide as the first", y));
+        }
         Ok(Grid::new(tiles))
     }
 }
 
+#[tracing::instrument(skip(input))]
 pub fn run(input: &str) -> u32 {
     let grid = Grid::from_str(input).unwrap();
     grid.shortest_path()
@@ -162,6 +178,9 @@ pub fn run(input: &str) -> u32 {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -171,4 +190,97 @@ mod tests {
         let output = run(input);
         assert_eq!(output, 102);
     }
+
+    #[test]
+    fn invalid_grids() {
+        assert!(Grid::from_str("12\n34\n").is_ok());
+        assert!(Grid::from_str("").is_err());
+        assert!(Grid::from_str("12\n3\n").is_err());
+        assert!(Grid::from_str("1a\n").is_err());
+    }
+
+    /// Relaxes every (block, direction, blocks moved straight) state until
+    /// nothing improves, rather than searching outward from the start.
+    fn reference(rows: &[Vec<u32>], min: usize, max: usize) -> Option<u32> {
+        let (height, width) = (rows.len() as i32, rows[0].len() as i32);
+        let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+        let mut best: HashMap<(i32, i32, usize, usize), u32> = HashMap::new();
+        for (direction, &(dx, dy)) in directions.iter().enumerate() {
+            if (0..width).contains(&dx) && (0..height).contains(&dy) {
+                best.insert((dx, dy, direction, 1), rows[dy as usize][dx as usize]);
+            }
+        }
+        loop {
+            let mut improved = false;
+            for ((x, y, direction, straight), cost) in best.clone() {
+                for (turn, &(dx, dy)) in directions.iter().enumerate() {
+                    let (next_x, next_y) = (x + dx, y + dy);
+                    let allowed = if turn == direction {
+                        straight < max
+                    } else {
+                        turn != (direction + 2) % 4 && straight >= min
+                    };
+                    if !allowed || !(0..width).contains(&next_x) || !(0..height).contains(&next_y) {
+                        continue;
+                    }
+                    let next_straight = if turn == direction { straight + 1 } else { 1 };
+                    let next_cost = cost + rows[next_y as usize][next_x as usize];
+                    let key = (next_x, next_y, turn, next_straight);
+                    if best.get(&key).is_none_or(|&known| next_cost < known) {
+                        best.insert(key, next_cost);
+                        improved = true;
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+        if (width, height) == (1, 1) {
+            return Some(0);
+        }
+        best.into_iter()
+            .filter(|&((x, y, _, straight), _)| {
+                (x, y) == (width - 1, height - 1) && straight >= min
+            })
+            .map(|(_, cost)| cost)
+            .min()
+    }
+
+    fn grid(sizes: std::ops::Range<usize>) -> impl Strategy<Value = String> {
+        (sizes.clone(), sizes).prop_flat_map(|(width, height)| {
+            prop::collection::vec(prop::collection::vec(1..=9u32, width), height).prop_map(|rows| {
+                rows.iter()
+                    .map(|row| row.iter().map(u32::to_string).collect::<String>() + "\n")
+                    .collect()
+            })
+        })
+    }
+
+    fn digits(input: &str) -> Vec<Vec<u32>> {
+        input
+            .lines()
+            .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = Grid::from_str(&input);
+        }
+
+        #[test]
+        fn parsed_grids_never_panic(input in "[0-9x\n]{0,40}") {
+            if Grid::from_str(&input).is_ok() {
+                run(&input);
+            }
+        }
+
+        #[test]
+        fn agrees_with_reference(input in grid(1..7)) {
+            let expected = reference(&digits(&input), 1, 3).unwrap_or(u32::MAX);
+            prop_assert_eq!(run(&input), expected);
+        }
+    }
 }
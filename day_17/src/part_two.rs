@@ -42,30 +42,65 @@ This route causes the ultra crucible to incur the minimum possible heat loss of
 Directing the ultra crucible from the lava pool to the machine parts factory, what is the least heat loss it can incur?
 */
 
-use itertools::Itertools;
-use pathfinding::prelude::dijkstra;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use std::collections::{HashMap, VecDeque};
+use pathfinding::prelude::{dijkstra, dijkstra_all};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+const MIN_STRAIGHT: u8 = 4;
+const MAX_STRAIGHT: u8 = 10;
+
+const DIRECTIONS: [Position; 4] = [
+    Position { x: 1, y: 0 },
+    Position { x: -1, y: 0 },
+    Position { x: 0, y: -1 },
+    Position { x: 0, y: 1 },
+];
 
 #[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
-struct Position {
-    x: i32,
-    y: i32,
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
 }
 
 impl Position {
-    fn new(x: i32, y: i32) -> Self {
+    pub fn new(x: i32, y: i32) -> Self {
         Position { x, y }
     }
 }
 
-struct Grid {
+/// A crucible on the map: where it is, which way it last moved and how many
+/// blocks it has moved in that direction without turning.
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
+struct State {
+    position: Position,
+    direction: Position,
+    straight: u8,
+}
+
+impl State {
+    fn start(position: Position) -> Self {
+        State {
+            position,
+            direction: Position::new(0, 0),
+            straight: 0,
+        }
+    }
+
+    /// The crucible may only come to a stop after moving at least
+    /// `MIN_STRAIGHT` blocks, except on the block it started from.
+    fn can_stop(&self) -> bool {
+        self.straight == 0 || self.straight >= MIN_STRAIGHT
+    }
+}
+
+pub struct Grid {
     boundaries: Position,
     grid: HashMap<Position, u32>,
 }
 
 impl Grid {
-    fn parse_grid(input: &str) -> Self {
+    #[tracing::instrument(name = "parse", skip_all)]
+    pub fn parse_grid(input: &str) -> Self {
         let mut grid = HashMap::new();
         for (y, line) in input.lines().enumerate() {
             for (x, char) in line.chars().enumerate() {
@@ -75,153 +110,290 @@ impl Grid {
             }
         }
         let boundaries = Position::new(
-            input.lines().next().unwrap().len() as i32,
+            input.lines().next().unwrap_or_default().len() as i32,
             input.lines().count() as i32,
         );
         Grid { boundaries, grid }
     }
 
-    fn successors(
-        &self,
-        (position, deque): &(Position, VecDeque<Position>),
-    ) -> Vec<((Position, VecDeque<Position>), u32)> {
-        let diffs: Vec<Position> = deque
-            .iter()
-            .tuple_windows()
-            .map(|(a, b)| Position::new(a.x - b.x, a.y - b.y))
-            .collect();
-        let last_diff = diffs.get(0);
-
-        let maybe_first_diff_count = diffs.iter().dedup_with_count().next();
-        let options = if let Some(diff_count) = maybe_first_diff_count {
-            let num_consecutive_straight_diffs = diff_count.0;
-            let must_turn = num_consecutive_straight_diffs >= 10;
-            let must_go_straight = num_consecutive_straight_diffs < 4;
-
-            if must_turn {
-                [
-                    Position::new(1, 0),
-                    Position::new(-1, 0),
-                    Position::new(0, -1),
-                    Position::new(0, 1),
-                ]
-                .into_iter()
-                .filter(|option| Some(option) != last_diff)
-                .collect::<Vec<Position>>()
-            } else if must_go_straight {
-                vec![*last_diff.unwrap()]
-            } else {
-                vec![
-                    Position::new(1, 0),
-                    Position::new(-1, 0),
-                    Position::new(0, -1),
-                    Position::new(0, 1),
-                ]
-            }
-        } else {
-            vec![
-                Position::new(1, 0),
-                Position::new(-1, 0),
-                Position::new(0, -1),
-                Position::new(0, 1),
-            ]
-        };
-
-        options
-            .into_par_iter()
-            .filter_map(|pos_diff| {
-                let next_position = Position::new(position.x + pos_diff.x, position.y + pos_diff.y);
-                if (0..self.boundaries.x).contains(&next_position.x)
-                    && (0..self.boundaries.y).contains(&next_position.y)
-                {
-                    if deque.len() > 2 && deque[1] == next_position {
-                        return None;
-                    }
-
-                    let mut new_deque = deque.clone();
-                    new_deque.push_front(next_position);
+    pub fn boundaries(&self) -> Position {
+        self.boundaries
+    }
 
-                    if new_deque.len() > 14 {
-                        new_deque.pop_back();
-                    }
-                    Some((next_position, new_deque))
+    fn successors(&self, state: &State) -> Vec<(State, u32)> {
+        DIRECTIONS
+            .iter()
+            .filter(|&&direction| {
+                let reverse = Position::new(-state.direction.x, -state.direction.y);
+                let same_direction = direction == state.direction;
+                if state.straight == 0 {
+                    true
+                } else if direction == reverse {
+                    false
+                } else if state.straight < MIN_STRAIGHT {
+                    same_direction
                 } else {
-                    None
+                    !same_direction || state.straight < MAX_STRAIGHT
                 }
             })
-            .map(|pos| {
-                let next_cost = *self.grid.get(&pos.0).unwrap();
-                (pos, next_cost)
+            .filter_map(|&direction| {
+                let position = Position::new(
+                    state.position.x + direction.x,
+                    state.position.y + direction.y,
+                );
+                let cost = *self.grid.get(&position)?;
+                let straight = if direction == state.direction {
+                    state.straight + 1
+                } else {
+                    1
+                };
+                let next = State {
+                    position,
+                    direction,
+                    straight,
+                };
+                Some((next, cost))
             })
-            .collect::<Vec<((Position, VecDeque<Position>), u32)>>()
-    }
-
-    fn success(goal: &Position, (win, deque): &(Position, VecDeque<Position>)) -> bool {
-        let diffs: Vec<Position> = deque
-            .iter()
-            .tuple_windows()
-            .map(|(a, b)| Position::new(a.x - b.x, a.y - b.y))
-            .collect();
-        let maybe_first_diff_count = diffs.iter().dedup_with_count().next();
-
-        maybe_first_diff_count.is_some_and(|(count, _)| count >= 4) && win == goal
+            .collect()
     }
 
+    /// Finds the cheapest route from `start` to whichever of `goals` is
+    /// reached first, returning the visited blocks and the heat loss incurred.
+    #[tracing::instrument(skip_all)]
     pub fn shortest_path(
         &self,
         start: Position,
-        goal: Position,
-    ) -> (Vec<(Position, VecDeque<Position>)>, u32) {
-        let mut deque = VecDeque::new();
-        deque.push_back(start);
-
-        let result: (Vec<(Position, VecDeque<Position>)>, u32) = dijkstra(
-            &(start, deque),
-            |args| self.successors(&args),
-            |args| Self::success(&goal, args),
-        )
-        .expect("should have a valid path");
-
-        self.print();
-        result
+        goals: &HashSet<Position>,
+    ) -> Option<(Vec<Position>, u32)> {
+        if !self.grid.contains_key(&start) {
+            return None;
+        }
+        let (path, cost) = dijkstra(
+            &State::start(start),
+            |state| self.successors(state),
+            |state| state.can_stop() && goals.contains(&state.position),
+        )?;
+        let path = path.into_iter().map(|state| state.position).collect();
+        Some((path, cost))
     }
 
-    pub fn print(&self) {
-        for y in 0..self.boundaries.y {
-            for x in 0..self.boundaries.x {
-                match self.grid.get(&Position::new(x, y)) {
-                    Some(_) => {
-                        print!("#");
-                    }
-                    None => print!("."),
+    /// Computes the least heat loss from `start` to every block the crucible
+    /// can stop on.
+    pub fn distance_field(&self, start: Position) -> DistanceField {
+        let mut distances = HashMap::new();
+        if self.grid.contains_key(&start) {
+            let start = State::start(start);
+            distances.insert(start.position, 0);
+            for (state, (_, cost)) in dijkstra_all(&start, |state| self.successors(state)) {
+                if !state.can_stop() {
+                    continue;
                 }
+                distances
+                    .entry(state.position)
+                    .and_modify(|best: &mut u32| *best = (*best).min(cost))
+                    .or_insert(cost);
             }
-            println!();
         }
+        DistanceField {
+            boundaries: self.boundaries,
+            distances,
+        }
+    }
+}
+
+/// Least heat loss from a single start to every reachable block.
+pub struct DistanceField {
+    boundaries: Position,
+    distances: HashMap<Position, u32>,
+}
+
+impl DistanceField {
+    pub fn get(&self, position: &Position) -> Option<u32> {
+        self.distances.get(position).copied()
+    }
+
+    pub fn min_over<'a>(&self, positions: impl IntoIterator<Item = &'a Position>) -> Option<u32> {
+        positions
+            .into_iter()
+            .filter_map(|position| self.get(position))
+            .min()
+    }
+}
+
+impl fmt::Display for DistanceField {
+    /// Renders one row per line with unreachable blocks shown as `.`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .distances
+            .values()
+            .max()
+            .map_or(1, |max| max.to_string().len());
+        for y in 0..self.boundaries.y {
+            let row = (0..self.boundaries.x)
+                .map(|x| match self.get(&Position::new(x, y)) {
+                    Some(distance) => format!("{:>width$}", distance),
+                    None => format!("{:>width$}", "."),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
     }
 }
 
+#[tracing::instrument(skip(input))]
 pub fn run(input: &str) -> u32 {
-    let row_count = input.lines().count() as i32;
-    let column_count = input.lines().next().unwrap().len() as i32;
-    let goal = Position::new(column_count - 1, row_count - 1);
+    let grid = Grid::parse_grid(input);
+    let boundaries = grid.boundaries();
     let start = Position::new(0, 0);
+    let goal = Position::new(boundaries.x - 1, boundaries.y - 1);
 
-    let grid = Grid::parse_grid(input);
-    let result = grid.shortest_path(start, goal);
+    let result = grid
+        .shortest_path(start, &HashSet::from([goal]))
+        .expect("should have a valid path");
 
     result.1
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
 
     use super::*;
 
+    /// Relaxes every (block, direction, blocks moved straight) state until
+    /// nothing improves, rather than searching outward from the start.
+    fn reference(rows: &[Vec<u32>], min: usize, max: usize) -> Option<u32> {
+        let (height, width) = (rows.len() as i32, rows[0].len() as i32);
+        let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+        let mut best: HashMap<(i32, i32, usize, usize), u32> = HashMap::new();
+        for (direction, &(dx, dy)) in directions.iter().enumerate() {
+            if (0..width).contains(&dx) && (0..height).contains(&dy) {
+                best.insert((dx, dy, direction, 1), rows[dy as usize][dx as usize]);
+            }
+        }
+        loop {
+            let mut improved = false;
+            for ((x, y, direction, straight), cost) in best.clone() {
+                for (turn, &(dx, dy)) in directions.iter().enumerate() {
+                    let (next_x, next_y) = (x + dx, y + dy);
+                    let allowed = if turn == direction {
+                        straight < max
+                    } else {
+                        turn != (direction + 2) % 4 && straight >= min
+                    };
+                    if !allowed || !(0..width).contains(&next_x) || !(0..height).contains(&next_y) {
+                        continue;
+                    }
+                    let next_straight = if turn == direction { straight + 1 } else { 1 };
+                    let next_cost = cost + rows[next_y as usize][next_x as usize];
+                    let key = (next_x, next_y, turn, next_straight);
+                    if best.get(&key).is_none_or(|&known| next_cost < known) {
+                        best.insert(key, next_cost);
+                        improved = true;
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+        if (width, height) == (1, 1) {
+            return Some(0);
+        }
+        best.into_iter()
+            .filter(|&((x, y, _, straight), _)| {
+                (x, y) == (width - 1, height - 1) && straight >= min
+            })
+            .map(|(_, cost)| cost)
+            .min()
+    }
+
+    fn grid(sizes: std::ops::Range<usize>) -> impl Strategy<Value = String> {
+        (sizes.clone(), sizes).prop_flat_map(|(width, height)| {
+            prop::collection::vec(prop::collection::vec(1..=9u32, width), height).prop_map(|rows| {
+                rows.iter()
+                    .map(|row| row.iter().map(u32::to_string).collect::<String>() + "\n")
+                    .collect()
+            })
+        })
+    }
+
+    fn digits(input: &str) -> Vec<Vec<u32>> {
+        input
+            .lines()
+            .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
+            .collect()
+    }
+
     #[test]
     fn part_2() {
         let input = include_str!("../test_data.txt");
         let output = run(input);
         assert_eq!(output, 94);
     }
+
+    #[test]
+    fn part_2_unfortunate_path() {
+        let input = "111111111111\n999999999991\n999999999991\n999999999991\n999999999991";
+        let output = run(input);
+        assert_eq!(output, 71);
+    }
+
+    #[test]
+    fn distance_field_matches_shortest_path() {
+        let input = include_str!("../test_data.txt");
+        let grid = Grid::parse_grid(input);
+        let start = Position::new(3, 2);
+        let field = grid.distance_field(start);
+
+        assert_eq!(field.get(&start), Some(0));
+        for y in 0..grid.boundaries().y {
+            for x in 0..grid.boundaries().x {
+                let goal = Position::new(x, y);
+                let expected = grid
+                    .shortest_path(start, &HashSet::from([goal]))
+                    .map(|(_, cost)| cost);
+                assert_eq!(field.get(&goal), expected, "goal {:?}", goal);
+            }
+        }
+    }
+
+    #[test]
+    fn multiple_goals() {
+        let input = include_str!("../test_data.txt");
+        let grid = Grid::parse_grid(input);
+        let start = Position::new(0, 0);
+        let goals = HashSet::from([Position::new(12, 12), Position::new(12, 4)]);
+        let field = grid.distance_field(start);
+
+        let (path, cost) = grid.shortest_path(start, &goals).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert!(goals.contains(path.last().unwrap()));
+        assert_eq!(Some(cost), field.min_over(&goals));
+        assert!(cost <= 94);
+    }
+
+    #[test]
+    fn start_outside_grid() {
+        let grid = Grid::parse_grid(include_str!("../test_data.txt"));
+        let start = Position::new(-1, 0);
+        let goals = HashSet::from([Position::new(0, 0)]);
+        assert_eq!(grid.shortest_path(start, &goals), None);
+        assert_eq!(grid.distance_field(start).get(&Position::new(0, 0)), None);
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = Grid::parse_grid(&input);
+        }
+
+        #[test]
+        fn agrees_with_reference(input in grid(5..9)) {
+            let expected = reference(&digits(&input), MIN_STRAIGHT as usize, MAX_STRAIGHT as usize);
+            prop_assert_eq!(Some(run(&input)), expected);
+        }
+    }
 }
@@ -14,7 +14,7 @@ fn main() {
 
     let string = include_str!("../input.txt");
 
-    let part_one = part_one::run(string);
+    let part_one = part_one::run(string).unwrap();
     info!("Part One: {}", part_one);
 
     let part_two = part_two::run(string);
@@ -89,11 +89,21 @@ struct HexColor {
 impl FromStr for HexColor {
     type Err = String;
 
+    /// Parses `(#rrggbb)`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let red = u8::from_str_radix(&s[2..4], 16).unwrap();
-        let green = u8::from_str_radix(&s[4..6], 16).unwrap();
-        let blue = u8::from_str_radix(&s[6..8], 16).unwrap();
-        Ok(HexColor { red, green, blue })
+        let hex = s
+            .strip_prefix("(#")
+            .and_then(|s| s.strip_suffix(')'))
+            .filter(|hex| hex.len() == 6 && hex.is_ascii())
+            .ok_or_else(|| format!("Invalid color {:?}", s))?;
+        let channel = |range: std::ops::Range<usize>| {
+            u8::from_str_radix(&hex[range], 16).map_err(|_| format!("Invalid color {:?}", s))
+        };
+        Ok(HexColor {
+            red: channel(0..2)?,
+            green: channel(2..4)?,
+            blue: channel(4..6)?,
+        })
     }
 }
 
@@ -110,9 +120,18 @@ impl FromStr for Instruction {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.split_whitespace();
-        let direction = parts.next().unwrap().parse::<Direction>()?;
-        let distance = parts.next().unwrap().parse::<i32>().unwrap();
-        let color = parts.next().unwrap().parse::<HexColor>()?;
+        let (Some(direction), Some(distance), Some(color), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!("Invalid instruction {:?}", s));
+        };
+        let direction = direction.parse::<Direction>()?;
+        let distance = distance
+            .parse::<i32>()
+            .ok()
+            .filter(|distance| *distance >= 0)
+            .ok_or_else(|| format!("Invalid distance {:?}", distance))?;
+        let color = color.parse::<HexColor>()?;
         Ok(Instruction {
             direction,
             distance,
@@ -121,32 +140,33 @@ impl FromStr for Instruction {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
-struct Position(i32, i32);
+/// The dig plan, one instruction per line.
 #[derive(Debug)]
-struct Instructions(Vec<Instruction>);
+pub struct Instructions(Vec<Instruction>);
 
 impl FromStr for Instructions {
     type Err = String;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let instructions = s
             .lines()
-            .map(|line| line.parse::<Instruction>().unwrap())
-            .collect::<Vec<Instruction>>();
+            .map(|line| line.parse::<Instruction>())
+            .collect::<Result<Vec<Instruction>, _>>()?;
         Ok(Instructions(instructions))
     }
 }
 
 #[tracing::instrument(skip(input))]
-pub fn run(input: &str) -> i32 {
+pub fn run(input: &str) -> Result<i32, String> {
     let grid_size = 1000;
     let start_pos = (grid_size / 2, grid_size / 2);
     let mut position = start_pos;
     let mut trench = HashSet::new();
 
-    let instructions = Instructions::from_str(input).unwrap().0;
-    for instruction in instructions {
+    let instructions = Instructions::from_str(input)?.0;
+    let dig = tracing::info_span!("dig").entered();
+    for (index, instruction) in instructions.into_iter().enumerate() {
         let direction = instruction.direction;
         let length = instruction.distance;
         for _ in 0..length {
@@ -156,11 +176,21 @@ pub fn run(input: &str) -> i32 {
                 Direction::Left => position.0 -= 1,
                 Direction::Right => position.0 += 1,
             }
+            if !(0..grid_size).contains(&position.0) || !(0..grid_size).contains(&position.1) {
+                return Err(format!(
+                    "line {}: the trench leaves the {} by {} grid",
+                    index + 1,
+                    grid_size,
+                    grid_size
+                ));
+            }
             trench.insert(position);
         }
     }
+    dig.exit();
 
     // Flood fill to find the interior
+    let flood_fill = tracing::info_span!("flood_fill").entered();
     let mut outside = HashSet::new();
     let mut queue = VecDeque::new();
     queue.push_back((0, 0));
@@ -179,23 +209,126 @@ pub fn run(input: &str) -> i32 {
             }
         }
     }
+    flood_fill.exit();
 
-    let total_volume = (0..grid_size)
-        .flat_map(|x| (0..grid_size).map(move |y| (x, y)))
-        .filter(|&pos| trench.contains(&pos) || !outside.contains(&pos))
-        .count();
+    let total_volume = tracing::info_span!("count").in_scope(|| {
+        (0..grid_size)
+            .flat_map(|x| (0..grid_size).map(move |y| (x, y)))
+            .filter(|&pos| trench.contains(&pos) || !outside.contains(&pos))
+            .count()
+    });
 
-    total_volume as i32
+    Ok(total_volume as i32)
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn part_1() {
         let input = include_str!("../test_data.txt");
         let output = run(input);
-        assert_eq!(output, 62);
+        assert_eq!(output, Ok(62));
+    }
+
+    #[test]
+    fn trench_leaving_the_grid() {
+        assert_eq!(
+            run("R 6 (#70c710)\nD 500 (#0dc571)\n"),
+            Err("line 2: the trench leaves the 1000 by 1000 grid".to_string())
+        );
+        assert!(run("L 2147483647 (#70c710)").is_err());
+    }
+
+    #[test]
+    fn invalid_instructions() {
+        assert!(Instructions::from_str("R 6 (#70c710)\nD 5 (#0dc571)\n").is_ok());
+        assert!(Instructions::from_str("R 6").is_err());
+        assert!(Instructions::from_str("X 6 (#70c710)").is_err());
+        assert!(Instructions::from_str("R -6 (#70c710)").is_err());
+        assert!(Instructions::from_str("R 6 (#70c71)").is_err());
+        assert!(Instructions::from_str("R 6 (#70c7é)").is_err());
+        assert!(Instructions::from_str("R 6 (#70c710) extra").is_err());
+    }
+
+    /// The shoelace formula for the area enclosed by the middle of the
+    /// trench, plus the outer half of the trench itself.
+    fn reference(input: &str) -> i64 {
+        let (mut x, mut y, mut area, mut perimeter) = (0i64, 0i64, 0i64, 0i64);
+        for line in input.lines() {
+            let mut parts = line.split_whitespace();
+            let direction = parts.next().unwrap();
+            let distance: i64 = parts.next().unwrap().parse().unwrap();
+            let (dx, dy) = match direction {
+                "U" => (0, -1),
+                "D" => (0, 1),
+                "L" => (-1, 0),
+                _ => (1, 0),
+            };
+            let (next_x, next_y) = (x + dx * distance, y + dy * distance);
+            area += x * next_y - next_x * y;
+            perimeter += distance;
+            (x, y) = (next_x, next_y);
+        }
+        area.abs() / 2 + perimeter / 2 + 1
+    }
+
+    /// Closed dig plans around a skyline of columns, which never cross
+    /// themselves.
+    fn dig_plan() -> impl Strategy<Value = String> {
+        let column = (1..6i32, 1..6i32);
+        let color = "\\(#[0-9a-f]{6}\\)";
+        (
+            prop::collection::vec(column, 1..6),
+            prop::collection::vec(color, 32),
+        )
+            .prop_map(|(columns, colors)| {
+                let mut steps = Vec::new();
+                let mut height = 0;
+                for &(width, next_height) in &columns {
+                    match next_height - height {
+                        0 => {}
+                        rise if rise > 0 => steps.push(("U", rise)),
+                        fall => steps.push(("D", -fall)),
+                    }
+                    steps.push(("R", width));
+                    height = next_height;
+                }
+                steps.push(("D", height));
+                steps.push(("L", columns.iter().map(|(width, _)| width).sum()));
+                steps
+                    .iter()
+                    .zip(colors.iter().cycle())
+                    .map(|((direction, distance), color)| {
+                        format!("{} {} {}\n", direction, distance, color)
+                    })
+                    .collect()
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = Instructions::from_str(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "[UDLRX] -?[0-9]{0,3} \\(#[0-9a-fé]{0,7}\\)?") {
+            let _ = Instructions::from_str(&input);
+        }
+    }
+
+    proptest! {
+        // Every run floods a million blocks, so only a handful of plans.
+        #![proptest_config(ProptestConfig::with_cases(4))]
+
+        #[test]
+        fn agrees_with_reference(input in dig_plan()) {
+            prop_assert!(Instructions::from_str(&input).is_ok());
+            prop_assert_eq!(run(&input).map(i64::from), Ok(reference(&input)));
+        }
     }
 }
@@ -42,7 +42,6 @@ This route causes the ultra crucible to incur the minimum possible heat loss of
 Directing the ultra crucible from the lava pool to the machine parts factory, what is the least heat loss it can incur?
 */
 
-#[tracing::instrument(skip(_input))]
 pub fn run(_input: &str) -> u32 {
     0
 }
@@ -0,0 +1,168 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use nom::{character::complete::multispace0, combinator::all_consuming, sequence::delimited};
+
+use crate::game::{Draw, Game};
+
+/// How many cubes of each colour the bag was loaded with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bag {
+    cubes: HashMap<String, u32>,
+}
+
+impl Bag {
+    pub fn new<I, S>(cubes: I) -> Self
+    where
+        I: IntoIterator<Item = (S, u32)>,
+        S: Into<String>,
+    {
+        let cubes = cubes
+            .into_iter()
+            .map(|(color, count)| (color.into(), count))
+            .collect();
+        Self { cubes }
+    }
+
+    pub fn count(&self, color: &str) -> Option<u32> {
+        self.cubes.get(color).copied()
+    }
+
+    /// Whether every draw of `game` fits in the bag. A colour the bag knows
+    /// nothing about is an error rather than an empty slot.
+    pub fn allows(&self, game: &Game) -> Result<bool, String> {
+        for draw in &game.draws {
+            for &(color, count) in &draw.cubes {
+                let available = self
+                    .count(color)
+                    .ok_or_else(|| format!("Game {}: unknown colour {}", game.id, color))?;
+                if count > available {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// The smallest bag under which every game in `ids` is possible.
+    ///
+    /// Adding cubes never makes a possible game impossible, so the Pareto set
+    /// of minimal bags always has exactly one member: the per-colour maximum
+    /// over the requested games. Colours only the other games show hold no
+    /// cubes, so the bag can still be asked about every game.
+    pub fn minimal_for(games: &[Game], ids: &[u32]) -> Result<Self, String> {
+        let mut cubes: HashMap<String, u32> = games
+            .iter()
+            .flat_map(|game| &game.draws)
+            .flat_map(|draw| &draw.cubes)
+            .map(|&(color, _)| (color.to_string(), 0))
+            .collect();
+        for id in ids {
+            let game = games
+                .iter()
+                .find(|game| game.id == *id)
+                .ok_or_else(|| format!("Game {}: not found", id))?;
+            for (color, count) in game.max_cubes() {
+                let max = cubes.entry(color.to_string()).or_insert(0);
+                *max = (*max).max(count);
+            }
+        }
+        Ok(Self { cubes })
+    }
+}
+
+impl Default for Bag {
+    fn default() -> Self {
+        Self::new([("red", 12), ("green", 13), ("blue", 14)])
+    }
+}
+
+impl fmt::Display for Bag {
+    /// Writes the bag the way it is parsed, colours in alphabetical order.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut cubes: Vec<_> = self.cubes.iter().collect();
+        cubes.sort();
+        let cubes: Vec<String> = cubes
+            .iter()
+            .map(|(color, count)| format!("{} {}", count, color))
+            .collect();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+impl FromStr for Bag {
+    type Err = String;
+
+    /// Parses a bag written like a draw, e.g. `12 red, 13 green, 14 blue`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (_, draw) = all_consuming(delimited(multispace0, Draw::parse, multispace0))(s)
+            .map_err(|e| format!("Invalid bag {:?}: {}", s, e))?;
+        Ok(Self::new(draw.cubes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAMES: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn test_from_str() {
+        let bag: Bag = "12 red, 13 green, 14 blue".parse().unwrap();
+        assert_eq!(bag, Bag::default());
+        assert!("12 red, green".parse::<Bag>().is_err());
+        assert!("12 red, 13 red".parse::<Bag>().is_err());
+    }
+
+    #[test]
+    fn test_allows_unknown_colour() {
+        let games = Game::parse_all("Game 9: 1 red, 2 purple").unwrap();
+        let bag = Bag::default();
+        assert_eq!(
+            bag.allows(&games[0]),
+            Err("Game 9: unknown colour purple".to_string())
+        );
+
+        let bag = Bag::new([("red", 1), ("purple", 2)]);
+        assert_eq!(bag.allows(&games[0]), Ok(true));
+    }
+
+    #[test]
+    fn test_minimal_for() {
+        let games = Game::parse_all(GAMES).unwrap();
+        let bag = Bag::minimal_for(&games, &[1, 2, 5]).unwrap();
+        assert_eq!(bag, Bag::new([("red", 6), ("green", 3), ("blue", 6)]));
+        for game in &games {
+            let expected = [1, 2, 5].contains(&game.id);
+            assert_eq!(bag.allows(game), Ok(expected));
+        }
+
+        assert_eq!(
+            Bag::minimal_for(&games, &[1, 6]),
+            Err("Game 6: not found".to_string())
+        );
+    }
+
+    #[test]
+    fn test_minimal_for_colours_of_other_games() {
+        let games = Game::parse_all("Game 1: 2 red\nGame 2: 1 red, 3 purple").unwrap();
+        let bag = Bag::minimal_for(&games, &[1]).unwrap();
+        assert_eq!(bag, Bag::new([("red", 2), ("purple", 0)]));
+        assert_eq!(bag.allows(&games[0]), Ok(true));
+        assert_eq!(bag.allows(&games[1]), Ok(false));
+
+        let bag = Bag::minimal_for(&games, &[]).unwrap();
+        assert_eq!(bag, Bag::new([("red", 0), ("purple", 0)]));
+    }
+
+    #[test]
+    fn test_display() {
+        let bag = Bag::new([("red", 2), ("purple", 0)]);
+        assert_eq!(bag.to_string(), "0 purple, 2 red");
+        assert_eq!(bag.to_string().parse::<Bag>(), Ok(bag));
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, line_ending, multispace0, space1, u32},
+    combinator::{all_consuming, map, verify},
+    multi::separated_list1,
+    sequence::{delimited, separated_pair},
+    IResult,
+};
+
+/// One handful of cubes shown from the bag. Every colour appears at most
+/// once, so each entry is the whole count of its colour.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Draw<'a> {
+    pub cubes: Vec<(&'a str, u32)>,
+}
+
+impl<'a> Draw<'a> {
+    pub(crate) fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        let cube = map(separated_pair(u32, space1, alpha1), |(count, color)| {
+            (color, count)
+        });
+        let distinct = |cubes: &[(&str, u32)]| {
+            cubes
+                .iter()
+                .enumerate()
+                .all(|(index, (color, _))| cubes[..index].iter().all(|(c, _)| c != color))
+        };
+        map(
+            verify(separated_list1(tag(", "), cube), distinct),
+            |cubes| Self { cubes },
+        )(input)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game<'a> {
+    pub id: u32,
+    pub draws: Vec<Draw<'a>>,
+}
+
+impl<'a> Game<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        let (input, id) = delimited(tag("Game "), u32, tag(": "))(input)?;
+        let (input, draws) = separated_list1(tag("; "), Draw::parse)(input)?;
+        Ok((input, Self { id, draws }))
+    }
+
+    #[tracing::instrument(name = "parse", skip_all)]
+    pub fn parse_all(input: &'a str) -> Result<Vec<Self>, nom::Err<nom::error::Error<&'a str>>> {
+        let games = separated_list1(line_ending, Game::parse);
+        let (_, games) = all_consuming(delimited(multispace0, games, multispace0))(input)?;
+        Ok(games)
+    }
+
+    /// The fewest cubes of each colour the bag must have held for this game.
+    pub fn max_cubes(&self) -> HashMap<&'a str, u32> {
+        let mut max_cubes = HashMap::new();
+        for draw in &self.draws {
+            for &(color, count) in &draw.cubes {
+                let max = max_cubes.entry(color).or_insert(0);
+                *max = (*max).max(count);
+            }
+        }
+        max_cubes
+    }
+
+    /// The product of `max_cubes`, or `None` when it overflows a `u32`.
+    pub fn power(&self) -> Option<u32> {
+        self.max_cubes()
+            .values()
+            .try_fold(1u32, |power, &count| power.checked_mul(count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_all() {
+        let input = "Game 7: 3 blue, 4 red; 2 green\r\nGame 2: 1 blue\n";
+        let games = Game::parse_all(input).unwrap();
+        assert_eq!(
+            games,
+            vec![
+                Game {
+                    id: 7,
+                    draws: vec![
+                        Draw {
+                            cubes: vec![("blue", 3), ("red", 4)]
+                        },
+                        Draw {
+                            cubes: vec![("green", 2)]
+                        },
+                    ],
+                },
+                Game {
+                    id: 2,
+                    draws: vec![Draw {
+                        cubes: vec![("blue", 1)]
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_all_rejects_malformed_games() {
+        assert!(Game::parse_all("Game 1: 3 blue, 4").is_err());
+        assert!(Game::parse_all("Game one: 3 blue").is_err());
+        assert!(Game::parse_all("Game 1: 3 blue\nGame 2 3 red").is_err());
+    }
+
+    #[test]
+    fn test_parse_all_rejects_repeated_colours() {
+        assert!(Game::parse_all("Game 1: 10 blue, 10 blue").is_err());
+        assert!(Game::parse_all("Game 1: 1 red, 2 blue, 3 red; 1 green").is_err());
+        assert!(Game::parse_all("Game 1: 10 blue; 10 blue").is_ok());
+    }
+
+    #[test]
+    fn test_max_cubes_and_power() {
+        let games =
+            Game::parse_all("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+        let max_cubes = games[0].max_cubes();
+        assert_eq!(max_cubes["red"], 4);
+        assert_eq!(max_cubes["green"], 2);
+        assert_eq!(max_cubes["blue"], 6);
+        assert_eq!(games[0].power(), Some(48));
+
+        let games = Game::parse_all("Game 1: 65536 red, 65536 blue").unwrap();
+        assert_eq!(games[0].power(), None);
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = Game::parse_all(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "[Game0-9:;, redgblu\n]{0,48}") {
+            let _ = Game::parse_all(&input);
+        }
+    }
+}
@@ -1,2 +1,4 @@
+pub mod bag;
+pub mod game;
 pub mod part_one;
 pub mod part_two;
@@ -1,27 +1,37 @@
-use std::fs;
+use std::env;
 
-use day_2::{part_one::PartOne, part_two::PartTwo};
-
-fn read_file_to_vector(path: &str) -> Vec<String> {
-    let mut return_vec: Vec<String> = Vec::new();
-    let file = fs::read(path).expect("Unable to read file");
-    let file_string = String::from_utf8_lossy(&file);
-    let file_string = file_string.to_string();
-    let file_string = file_string.trim();
-    let file_string = file_string.split("\n");
-    for line in file_string {
-        return_vec.push(line.to_string());
-    }
-    return_vec
-}
+use day_2::{bag::Bag, game::Game, part_one::PartOne, part_two::PartTwo};
 
 fn main() {
-    let input_data = read_file_to_vector("./input.txt");
-    let part_one = PartOne::new(&input_data);
-    let part_one_result = part_one.run();
+    let string = include_str!("../input.txt");
+    let games = Game::parse_all(string).expect("Unable to parse games");
+
+    // `--minimal-bag <ids>` prints the smallest bag under which the games
+    // with the comma separated ids are possible instead.
+    let mut args = env::args().skip(1);
+    let bag = match args.next() {
+        Some(flag) if flag == "--minimal-bag" => {
+            let ids = args
+                .next()
+                .unwrap_or_default()
+                .split(',')
+                .filter(|id| !id.trim().is_empty())
+                .map(|id| id.trim().parse::<u32>())
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Unable to parse game ids");
+            let bag = Bag::minimal_for(&games, &ids).expect("Unable to find a minimal bag");
+            println!("Minimal bag: {}", bag);
+            return;
+        }
+        Some(bag) => bag.parse::<Bag>().expect("Unable to parse bag"),
+        None => Bag::default(),
+    };
+
+    let part_one = PartOne::new(&games).with_bag(bag);
+    let part_one_result = part_one.run().expect("Unable to check games");
     println!("Part One Result: {}", part_one_result);
 
-    let part_two = PartTwo::new(&input_data);
-    let part_two_result = part_two.run();
+    let part_two = PartTwo::new(&games);
+    let part_two_result = part_two.run().expect("Unable to add up the powers");
     println!("Part Two Result: {}", part_two_result);
 }
@@ -25,58 +25,173 @@ In the example above, games 1, 2, and 5 would have been possible if the bag had
 
 Determine which games would have been possible if the bag had been loaded with only 12 red cubes, 13 green cubes, and 14 blue cubes. What is the sum of the IDs of those games?
 */
-use std::collections::HashMap;
-
-use lazy_static::lazy_static;
-
-lazy_static! {
-    static ref COLOR_MAP: HashMap<&'static str, i32> = {
-        let mut map = HashMap::new();
-        map.insert("red", 12);
-        map.insert("green", 13);
-        map.insert("blue", 14);
-        map
-    };
-}
+use crate::{bag::Bag, game::Game};
 
 pub struct PartOne<'a> {
-    input_data: &'a Vec<String>,
+    games: &'a [Game<'a>],
+    bag: Bag,
 }
 
 impl<'a> PartOne<'a> {
-    pub fn new(input_data: &'a Vec<String>) -> Self {
-        Self { input_data }
+    pub fn new(games: &'a [Game<'a>]) -> Self {
+        Self {
+            games,
+            bag: Bag::default(),
+        }
+    }
+
+    pub fn with_bag(mut self, bag: Bag) -> Self {
+        self.bag = bag;
+        self
+    }
+
+    pub fn is_game_possible(&self, game: &Game) -> Result<bool, String> {
+        self.bag.allows(game)
+    }
+
+    pub fn run(&self) -> Result<i32, String> {
+        let mut sum: i32 = 0;
+        for game in self.games {
+            if self.is_game_possible(game)? {
+                sum = i32::try_from(game.id)
+                    .ok()
+                    .and_then(|id| sum.checked_add(id))
+                    .ok_or_else(|| {
+                        format!("Game {}: the sum of the IDs overflows an i32", game.id)
+                    })?;
+            }
+        }
+        Ok(sum)
     }
+}
 
-    pub fn is_game_possible(&self, game: &str) -> bool {
-        game.split(": ")
-            .nth(1) // skip the game id
-            .unwrap()
-            .split(';')
-            .all(|game_part| {
-                game_part
-                    .split(',')
-                    .map(|color_count| {
-                        let parts: Vec<&str> = color_count.trim().split_whitespace().collect();
-                        let count = parts[0].parse::<i32>().unwrap();
-                        let color = parts[1];
-                        (color, count)
+#[tracing::instrument(skip(input))]
+pub fn run(input: &str) -> Result<i32, String> {
+    let games = Game::parse_all(input).map_err(|e| e.to_string())?;
+    PartOne::new(&games).run()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+        let games = Game::parse_all(input).unwrap();
+        assert_eq!(PartOne::new(&games).run(), Ok(8));
+    }
+
+    #[test]
+    fn test_run_uses_game_ids() {
+        let input = "Game 12: 6 red, 1 blue, 3 green
+Game 3: 8 green, 6 blue, 20 red
+Game 40: 1 blue, 2 green";
+        let games = Game::parse_all(input).unwrap();
+        assert_eq!(PartOne::new(&games).run(), Ok(52));
+    }
+
+    #[test]
+    fn test_run_with_bag() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 yellow, 2 green
+Game 3: 8 green, 6 yellow, 20 red";
+        let games = Game::parse_all(input).unwrap();
+
+        let bag = Bag::new([("red", 20), ("green", 8), ("blue", 6), ("yellow", 1)]);
+        assert_eq!(PartOne::new(&games).with_bag(bag).run(), Ok(3));
+
+        let result = PartOne::new(&games).run();
+        assert_eq!(result, Err("Game 2: unknown colour yellow".to_string()));
+    }
+
+    #[test]
+    fn test_run_overflow() {
+        let games = Game::parse_all("Game 2147483647: 1 red\nGame 1: 1 red").unwrap();
+        assert_eq!(
+            PartOne::new(&games).run(),
+            Err("Game 1: the sum of the IDs overflows an i32".to_string())
+        );
+
+        let games = Game::parse_all("Game 2147483648: 1 red").unwrap();
+        assert_eq!(
+            PartOne::new(&games).run(),
+            Err("Game 2147483648: the sum of the IDs overflows an i32".to_string())
+        );
+    }
+
+    /// Games of up to four draws, each showing some of red, green and blue.
+    fn games() -> impl Strategy<Value = String> {
+        let draw = prop::sample::subsequence(vec!["red", "green", "blue"], 1..=3)
+            .prop_flat_map(|colors| {
+                let count = colors.len();
+                (Just(colors), prop::collection::vec(1..=20u32, count))
+            })
+            .prop_map(|(colors, counts)| {
+                colors
+                    .iter()
+                    .zip(counts)
+                    .map(|(color, count)| format!("{} {}", count, color))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            });
+        let game = prop::collection::vec(draw, 1..=4).prop_map(|draws| draws.join("; "));
+        prop::collection::vec(game, 1..16).prop_map(|games| {
+            games
+                .iter()
+                .enumerate()
+                .map(|(index, game)| format!("Game {}: {}", index + 1, game))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    /// Splits every game into `(count, colour)` pairs by hand.
+    fn cubes(line: &str) -> (u32, Vec<Vec<(u32, &str)>>) {
+        let (game, draws) = line.split_once(": ").unwrap();
+        let id = game["Game ".len()..].parse().unwrap();
+        let draws = draws
+            .split("; ")
+            .map(|draw| {
+                draw.split(", ")
+                    .map(|cube| {
+                        let (count, color) = cube.split_once(' ').unwrap();
+                        (count.parse().unwrap(), color)
                     })
-                    .all(|(color, count)| count <= *COLOR_MAP.get(color).unwrap_or(&0))
+                    .collect()
             })
+            .collect();
+        (id, draws)
     }
 
-    pub fn run(&self) -> i32 {
-        self.input_data
-            .iter()
-            .enumerate()
-            .filter_map(|(index, game)| {
-                if self.is_game_possible(game) {
-                    Some(index as i32 + 1) // start with index 1
-                } else {
-                    None
-                }
+    /// Sums the ids of the games where no colour is ever shown more often
+    /// than 12 red, 13 green and 14 blue cubes allow.
+    fn reference(input: &str) -> i32 {
+        input
+            .lines()
+            .map(cubes)
+            .filter(|(_, draws)| {
+                draws.iter().flatten().all(|&(count, color)| match color {
+                    "red" => count <= 12,
+                    "green" => count <= 13,
+                    _ => count <= 14,
+                })
             })
+            .map(|(id, _)| id as i32)
             .sum()
     }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in games()) {
+            let games = Game::parse_all(&input).unwrap();
+            prop_assert_eq!(PartOne::new(&games).run(), Ok(reference(&input)));
+        }
+    }
 }
@@ -20,50 +20,140 @@ The power of a set of cubes is equal to the numbers of red, green, and blue cube
 
 For each game, find the minimum set of cubes that must have been present. What is the sum of the power of these sets?
 */
-use std::collections::HashMap;
+use crate::game::Game;
 
 pub struct PartTwo<'a> {
-    input_values: &'a [String],
+    games: &'a [Game<'a>],
 }
 
 impl<'a> PartTwo<'a> {
-    pub fn new(input_values: &'a [String]) -> Self {
-        Self { input_values }
+    pub fn new(games: &'a [Game<'a>]) -> Self {
+        Self { games }
     }
 
-    pub fn calculate_power_of_games(&self) -> Result<u32, &'static str> {
-        let mut total_power = 0;
+    pub fn calculate_power_of_games(&self) -> Result<u32, String> {
+        self.games.iter().try_fold(0u32, |sum, game| {
+            let power = game
+                .power()
+                .ok_or_else(|| format!("Game {}: the power overflows a u32", game.id))?;
+            sum.checked_add(power)
+                .ok_or_else(|| format!("Game {}: the sum of the powers overflows a u32", game.id))
+        })
+    }
+
+    pub fn run(&self) -> Result<u32, String> {
+        self.calculate_power_of_games()
+    }
+}
 
-        for game in self.input_values {
-            let (_, game_data) = game.split_once(": ").ok_or("Invalid game format")?;
+#[tracing::instrument(skip(input))]
+pub fn run(input: &str) -> Result<u32, String> {
+    let games = Game::parse_all(input).map_err(|e| e.to_string())?;
+    PartTwo::new(&games).run()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
 
-            let mut max_cubes: HashMap<&str, u32> = HashMap::new();
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+        let games = Game::parse_all(input).unwrap();
+        assert_eq!(PartTwo::new(&games).run(), Ok(2286));
+    }
 
-            for subset in game_data.split("; ") {
-                for color_count in subset.split(", ") {
-                    let mut parts = color_count.trim().split_whitespace();
-                    let count_str = parts.next().ok_or("Invalid color count format")?;
-                    let color = parts.next().ok_or("Invalid color count format")?;
+    #[test]
+    fn test_run_overflow() {
+        let games = Game::parse_all("Game 1: 1 red\nGame 7: 65536 red, 65536 blue").unwrap();
+        assert_eq!(
+            PartTwo::new(&games).run(),
+            Err("Game 7: the power overflows a u32".to_string())
+        );
 
-                    let count = count_str
-                        .parse::<u32>()
-                        .map_err(|_| "Invalid count format")?;
+        let games = Game::parse_all("Game 1: 4294967295 red\nGame 2: 1 red").unwrap();
+        assert_eq!(
+            PartTwo::new(&games).run(),
+            Err("Game 2: the sum of the powers overflows a u32".to_string())
+        );
+    }
 
-                    // If the color is not in the map, insert it with the count.
-                    // If the color is in the map, update the count if the new count is greater.
-                    *max_cubes.entry(color).or_insert(0) =
-                        max_cubes.get(color).map_or(count, |&c| c.max(count));
-                }
-            }
+    /// Games of up to four draws, each showing some of red, green and blue.
+    fn games() -> impl Strategy<Value = String> {
+        let draw = prop::sample::subsequence(vec!["red", "green", "blue"], 1..=3)
+            .prop_flat_map(|colors| {
+                let count = colors.len();
+                (Just(colors), prop::collection::vec(1..=20u32, count))
+            })
+            .prop_map(|(colors, counts)| {
+                colors
+                    .iter()
+                    .zip(counts)
+                    .map(|(color, count)| format!("{} {}", count, color))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            });
+        let game = prop::collection::vec(draw, 1..=4).prop_map(|draws| draws.join("; "));
+        prop::collection::vec(game, 1..16).prop_map(|games| {
+            games
+                .iter()
+                .enumerate()
+                .map(|(index, game)| format!("Game {}: {}", index + 1, game))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
 
-            let power = max_cubes.values().product::<u32>();
-            total_power += power;
-        }
+    /// Splits every game into `(count, colour)` pairs by hand.
+    fn cubes(line: &str) -> (u32, Vec<Vec<(u32, &str)>>) {
+        let (game, draws) = line.split_once(": ").unwrap();
+        let id = game["Game ".len()..].parse().unwrap();
+        let draws = draws
+            .split("; ")
+            .map(|draw| {
+                draw.split(", ")
+                    .map(|cube| {
+                        let (count, color) = cube.split_once(' ').unwrap();
+                        (count.parse().unwrap(), color)
+                    })
+                    .collect()
+            })
+            .collect();
+        (id, draws)
+    }
 
-        Ok(total_power)
+    /// Multiplies the most cubes shown of every colour the game shows.
+    fn reference(input: &str) -> u32 {
+        input
+            .lines()
+            .map(cubes)
+            .map(|(_, draws)| {
+                ["red", "green", "blue"]
+                    .iter()
+                    .filter_map(|&color| {
+                        draws
+                            .iter()
+                            .flatten()
+                            .filter(|&&(_, shown)| shown == color)
+                            .map(|&(count, _)| count)
+                            .max()
+                    })
+                    .product::<u32>()
+            })
+            .sum()
     }
 
-    pub fn run(&self) -> u32 {
-        self.calculate_power_of_games().unwrap_or(0)
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in games()) {
+            let games = Game::parse_all(&input).unwrap();
+            prop_assert_eq!(PartTwo::new(&games).run(), Ok(reference(&input)));
+        }
     }
 }
@@ -1,10 +1,6 @@
-use std::{fs::File, io::BufReader};
-
 pub mod part_one;
 pub mod part_two;
+pub mod report;
+pub mod schematic;
 
-pub fn file_reader(file_loc: &str) -> BufReader<File> {
-    let file = File::open(file_loc).unwrap();
-    let reader = BufReader::new(file);
-    reader
-}
+pub use common::file_reader;
@@ -1,11 +1,26 @@
-use day_3::{file_reader, part_one::PartOne, part_two::PartTwo};
+use std::{env, io};
 
-fn main() {
-    let buf_reader = file_reader("./input.txt");
-    let part_one = PartOne::new(buf_reader).run();
+use day_3::{
+    file_reader, part_one::PartOne, part_two::PartTwo, report::Report, schematic::Schematic,
+};
+
+fn main() -> io::Result<()> {
+    let buf_reader = file_reader("./input.txt")?;
+    let part_one = PartOne::new(buf_reader)?
+        .run()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
     println!("Part One: {}", part_one);
 
-    let buf_reader = file_reader("./input.txt");
-    let part_two = PartTwo::new(buf_reader).run();
+    let buf_reader = file_reader("./input.txt")?;
+    let part_two = PartTwo::new(buf_reader)?
+        .run()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
     println!("Part Two: {}", part_two);
+
+    if env::args().any(|arg| arg == "--report") {
+        let schematic = Schematic::parse(file_reader("./input.txt")?)?;
+        print!("{}", Report::new(&schematic));
+    }
+
+    Ok(())
 }
@@ -1,4 +1,3 @@
-use core::ops::Range;
 /**
 --- Day 3: Gear Ratios ---
 You and the Elf eventually reach a gondola lift station; he says the gondola lift will take you up to the water source, but this is as far as he can bring you. You go inside.
@@ -29,102 +28,120 @@ In this schematic, two numbers are not part numbers because they are not adjacen
 
 Of course, the actual engine schematic is much larger. What is the sum of all of the part numbers in the engine schematic?
 */
-use std::cmp::{max, min};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead};
 
-use regex::Regex;
-
-#[derive(Debug)]
-struct Symbol {
-    line_num: usize,
-    range: Range<usize>,
-}
-
-#[derive(Debug)]
-struct Number {
-    line_num: usize,
-    range: Range<usize>,
-    value: usize,
-}
+use crate::schematic::Schematic;
 
 pub struct PartOne {
-    numbers: Vec<Number>,
-    symbols: Vec<Symbol>,
+    schematic: Schematic,
 }
 impl PartOne {
-    fn parse_lines(buf_reader: BufReader<File>) -> (Vec<Number>, Vec<Symbol>) {
-        let num_regex = Regex::new(r"(\d+)").unwrap();
-        let symbol_regex = Regex::new(r"([^a-zA-z\d.\n])").unwrap();
-
-        let mut numbers = Vec::new();
-        let mut symbols = Vec::new();
-
-        for (index, line) in buf_reader.lines().map(Result::unwrap).enumerate() {
-            numbers.extend(num_regex.find_iter(&line).map(|m| Number {
-                line_num: index,
-                range: m.range(),
-                value: m.as_str().parse().unwrap(),
-            }));
-
-            symbols.extend(symbol_regex.find_iter(&line).map(|sym| Symbol {
-                line_num: index,
-                range: sym.range(),
-            }));
-        }
-
-        (numbers, symbols)
+    pub fn new(reader: impl BufRead) -> io::Result<Self> {
+        let schematic = Schematic::parse(reader)?;
+        Ok(Self { schematic })
     }
 
-    pub fn new(buf_reader: BufReader<File>) -> Self {
-        let (numbers, symbols) = Self::parse_lines(buf_reader);
-        Self { numbers, symbols }
+    pub fn run(&self) -> Result<usize, String> {
+        self.schematic.part_numbers().try_fold(0usize, |sum, num| {
+            sum.checked_add(num.value).ok_or_else(|| {
+                format!(
+                    "line {}: the sum of the part numbers overflows a usize",
+                    num.line_num + 1
+                )
+            })
+        })
     }
+}
 
-    pub fn run(&self) -> usize {
-        let mut part_sum = 0;
+#[tracing::instrument(skip(input))]
+pub fn run(input: &str) -> Result<usize, String> {
+    let part_one = PartOne::new(input.as_bytes()).map_err(|e| e.to_string())?;
+    part_one.run()
+}
 
-        for symbol in &self.symbols {
-            let symbol_range = symbol.range.start - 1..symbol.range.end + 1;
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
 
-            let adjacent_numbers: Vec<_> = self
-                .numbers
-                .iter()
-                .filter(|num| {
-                    let num_range = num.range.start..num.range.end;
-                    num.line_num >= symbol.line_num.saturating_sub(1)
-                        && num.line_num <= symbol.line_num + 1
-                        && num_range.overlaps(&symbol_range)
-                })
-                .collect();
+    use super::*;
 
-            part_sum += adjacent_numbers.iter().map(|num| num.value).sum::<usize>();
-        }
+    #[test]
+    fn test_part_one() {
+        let input = include_str!("../test_data.txt");
+        let part_one = PartOne::new(input.as_bytes()).unwrap().run();
+        assert_eq!(part_one, Ok(4361));
+    }
 
-        part_sum
+    #[test]
+    fn test_sum_overflow() {
+        let input = format!("1*\n{}*", usize::MAX);
+        assert_eq!(
+            run(&input),
+            Err("line 2: the sum of the part numbers overflows a usize".to_string())
+        );
     }
-}
 
-trait Overlaps {
-    fn overlaps(&self, other: &Self) -> bool;
-}
+    /// Up to eight columns, so that no part number or gear ratio overflows.
+    fn schematic() -> impl Strategy<Value = String> {
+        let row = prop::collection::vec(prop::sample::select(&b"......0123456789*#+$"[..]), 1..=8);
+        prop::collection::vec(row, 1..=8).prop_map(|rows| {
+            rows.iter()
+                .map(|row| String::from_utf8(row.clone()).unwrap())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
 
-impl Overlaps for Range<usize> {
-    fn overlaps(&self, other: &Self) -> bool {
-        max(self.start, other.start) < min(self.end, other.end)
+    /// Every number as `(value, row, first column, last column)`, found by
+    /// walking the rows byte by byte.
+    fn numbers(rows: &[&[u8]]) -> Vec<(usize, usize, usize, usize)> {
+        let mut numbers = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                if !row[x].is_ascii_digit() {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                let mut value = 0;
+                while x < row.len() && row[x].is_ascii_digit() {
+                    value = value * 10 + (row[x] - b'0') as usize;
+                    x += 1;
+                }
+                numbers.push((value, y, start, x - 1));
+            }
+        }
+        numbers
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::file_reader;
+    /// Whether the cell at `(x, y)` touches the number, diagonals included.
+    fn touches(number: &(usize, usize, usize, usize), x: usize, y: usize) -> bool {
+        let &(_, row, first, last) = number;
+        y + 1 >= row && y <= row + 1 && x + 1 >= first && x <= last + 1
+    }
 
-    use super::*;
+    /// Sums the numbers that touch any cell holding a symbol.
+    fn reference(input: &str) -> usize {
+        let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        numbers(&rows)
+            .iter()
+            .filter(|number| {
+                rows.iter().enumerate().any(|(y, row)| {
+                    row.iter().enumerate().any(|(x, &cell)| {
+                        cell != b'.' && !cell.is_ascii_digit() && touches(number, x, y)
+                    })
+                })
+            })
+            .map(|&(value, ..)| value)
+            .sum()
+    }
 
-    #[test]
-    fn test_part_one() {
-        let buf_reader = file_reader("./test_data.txt");
-        let part_one = PartOne::new(buf_reader).run();
-        assert_eq!(part_one, 4361);
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in schematic()) {
+            let part_one = PartOne::new(input.as_bytes()).unwrap();
+            prop_assert_eq!(part_one.run(), Ok(reference(&input)));
+        }
     }
 }
@@ -1,102 +1,165 @@
-use std::cmp::{max, min};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::ops::Range;
+/**
+--- Part Two ---
+The engineer finds the missing part and installs it in the engine! As the engine springs to life, you jump in the closest gondola, finally ready to ascend to the water source.
 
-use regex::Regex;
+You don't seem to be going very fast, though. Maybe something is still wrong? Fortunately, the gondola has a phone labeled "help", so you pick it up and the engineer answers.
 
-#[derive(Debug)]
-struct Symbol {
-    line_num: usize,
-    range: Range<usize>,
-}
+Before you can explain the situation, she suggests that you look out the window. There stands the engineer, holding a phone in one hand and waving with the other. You're going so slowly that you haven't even left the station. You exit the gondola.
 
-#[derive(Debug)]
-struct Number {
-    line_num: usize,
-    range: Range<usize>,
-    value: usize,
-}
+The missing part wasn't the only issue - one of the gears in the engine is wrong. A gear is any * symbol that is adjacent to exactly two part numbers. Its gear ratio is the result of multiplying those two numbers together.
 
-pub struct PartTwo {
-    numbers: Vec<Number>,
-    symbols: Vec<Symbol>,
-}
-impl PartTwo {
-    fn parse_lines(buf_reader: BufReader<File>) -> (Vec<Number>, Vec<Symbol>) {
-        let num_regex = Regex::new(r"(\d+)").unwrap();
-        let symbol_regex = Regex::new(r"([^a-zA-z\d.\n])").unwrap();
+This time, you need to find the gear ratio of every gear and add them all up so that the engineer can figure out which gear needs to be replaced.
 
-        let mut numbers = Vec::new();
-        let mut symbols = Vec::new();
-
-        for (index, line) in buf_reader.lines().map(Result::unwrap).enumerate() {
-            numbers.extend(num_regex.find_iter(&line).map(|m| Number {
-                line_num: index,
-                range: m.range(),
-                value: m.as_str().parse().unwrap(),
-            }));
-
-            symbols.extend(symbol_regex.find_iter(&line).map(|sym| Symbol {
-                line_num: index,
-                range: sym.range(),
-            }));
-        }
+Consider the same engine schematic again:
 
-        (numbers, symbols)
-    }
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+In this schematic, there are two gears. The first is in the top left; it has part numbers 467 and 35, so its gear ratio is 16345. The second gear is in the lower right; its gear ratio is 451490. (The * adjacent to 617 is not a gear because it is only adjacent to one part number.) Adding up all of the gear ratios produces 467835.
 
-    pub fn new(buf_reader: BufReader<File>) -> Self {
-        let (numbers, symbols) = Self::parse_lines(buf_reader);
-        Self { numbers, symbols }
-    }
+What is the sum of all of the gear ratios in your engine schematic?
+*/
+use std::io::{self, BufRead};
 
-    pub fn run(&self) -> usize {
-        let mut gear_product_sum = 0;
+use crate::schematic::Schematic;
 
-        for symbol in &self.symbols {
-            let symbol_range = symbol.range.start - 1..symbol.range.end + 1;
+pub struct PartTwo {
+    schematic: Schematic,
+}
+impl PartTwo {
+    pub fn new(reader: impl BufRead) -> io::Result<Self> {
+        let schematic = Schematic::parse(reader)?;
+        Ok(Self { schematic })
+    }
 
-            let adjacent_numbers: Vec<_> = self
-                .numbers
-                .iter()
-                .filter(|num| {
-                    let num_range = num.range.start..num.range.end;
-                    num.line_num >= symbol.line_num.saturating_sub(1)
-                        && num.line_num <= symbol.line_num + 1
-                        && num_range.overlaps(&symbol_range)
+    pub fn run(&self) -> Result<usize, String> {
+        self.schematic
+            .gears(2)
+            .filter(|(symbol, _)| symbol.symbol == '*')
+            .try_fold(0usize, |sum, (symbol, numbers)| {
+                let line = symbol.line_num + 1;
+                let ratio = numbers[0]
+                    .value
+                    .checked_mul(numbers[1].value)
+                    .ok_or_else(|| format!("line {}: the gear ratio overflows a usize", line))?;
+                sum.checked_add(ratio).ok_or_else(|| {
+                    format!(
+                        "line {}: the sum of the gear ratios overflows a usize",
+                        line
+                    )
                 })
-                .collect();
-
-            if adjacent_numbers.len() == 2 {
-                gear_product_sum += adjacent_numbers[0].value * adjacent_numbers[1].value;
-            }
-        }
-
-        gear_product_sum
+            })
     }
 }
 
-trait Overlaps {
-    fn overlaps(&self, other: &Self) -> bool;
-}
-
-impl Overlaps for Range<usize> {
-    fn overlaps(&self, other: &Self) -> bool {
-        max(self.start, other.start) < min(self.end, other.end)
-    }
+#[tracing::instrument(skip(input))]
+pub fn run(input: &str) -> Result<usize, String> {
+    let part_two = PartTwo::new(input.as_bytes()).map_err(|e| e.to_string())?;
+    part_two.run()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::file_reader;
+    use proptest::prelude::*;
 
     use super::*;
 
     #[test]
     fn test_part_two() {
-        let buf_reader = file_reader("./test_data.txt");
-        let part_two = PartTwo::new(buf_reader).run();
-        assert_eq!(part_two, 467835);
+        let input = include_str!("../test_data.txt");
+        let part_two = PartTwo::new(input.as_bytes()).unwrap().run();
+        assert_eq!(part_two, Ok(467835));
+    }
+
+    #[test]
+    fn test_gear_ratio_overflow() {
+        assert_eq!(
+            run("1*1\n4294967296*4294967296"),
+            Err("line 2: the gear ratio overflows a usize".to_string())
+        );
+
+        let input = format!("1*1\n\n1*{}", usize::MAX);
+        assert_eq!(
+            run(&input),
+            Err("line 3: the sum of the gear ratios overflows a usize".to_string())
+        );
+    }
+
+    /// Up to eight columns, so that no part number or gear ratio overflows.
+    fn schematic() -> impl Strategy<Value = String> {
+        let row = prop::collection::vec(prop::sample::select(&b"......0123456789*#+$"[..]), 1..=8);
+        prop::collection::vec(row, 1..=8).prop_map(|rows| {
+            rows.iter()
+                .map(|row| String::from_utf8(row.clone()).unwrap())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    /// Every number as `(value, row, first column, last column)`, found by
+    /// walking the rows byte by byte.
+    fn numbers(rows: &[&[u8]]) -> Vec<(usize, usize, usize, usize)> {
+        let mut numbers = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                if !row[x].is_ascii_digit() {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                let mut value = 0;
+                while x < row.len() && row[x].is_ascii_digit() {
+                    value = value * 10 + (row[x] - b'0') as usize;
+                    x += 1;
+                }
+                numbers.push((value, y, start, x - 1));
+            }
+        }
+        numbers
+    }
+
+    /// Whether the cell at `(x, y)` touches the number, diagonals included.
+    fn touches(number: &(usize, usize, usize, usize), x: usize, y: usize) -> bool {
+        let &(_, row, first, last) = number;
+        y + 1 >= row && y <= row + 1 && x + 1 >= first && x <= last + 1
+    }
+
+    /// Multiplies the numbers around every `*` touching exactly two of them.
+    fn reference(input: &str) -> usize {
+        let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        let numbers = numbers(&rows);
+        let mut sum = 0;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if cell != b'*' {
+                    continue;
+                }
+                let adjacent: Vec<usize> = numbers
+                    .iter()
+                    .filter(|number| touches(number, x, y))
+                    .map(|&(value, ..)| value)
+                    .collect();
+                if let [first, second] = adjacent[..] {
+                    sum += first * second;
+                }
+            }
+        }
+        sum
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in schematic()) {
+            let part_two = PartTwo::new(input.as_bytes()).unwrap();
+            prop_assert_eq!(part_two.run(), Ok(reference(&input)));
+        }
     }
 }
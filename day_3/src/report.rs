@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::schematic::{Number, Schematic, Symbol};
+
+/// Totals for every symbol of one character.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SymbolSummary {
+    /// How many times the character appears.
+    pub count: usize,
+    /// How many numbers are adjacent to it, counting a number once per symbol.
+    pub adjacent_numbers: usize,
+    /// Sum of the adjacent numbers.
+    pub sum: usize,
+    /// Sum of the products of the two numbers around each symbol with exactly
+    /// two neighbours.
+    pub gear_ratio_sum: usize,
+}
+
+/// Audit of a schematic: totals per symbol character, part numbers touching
+/// more than one symbol and numbers touching none.
+#[derive(Debug)]
+pub struct Report<'a> {
+    pub by_symbol: BTreeMap<char, SymbolSummary>,
+    pub shared_parts: Vec<(&'a Number, Vec<&'a Symbol>)>,
+    pub unattached: Vec<&'a Number>,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(schematic: &'a Schematic) -> Self {
+        let mut by_symbol: BTreeMap<char, SymbolSummary> = BTreeMap::new();
+        for symbol in schematic.symbols() {
+            let adjacent: Vec<_> = schematic.numbers_adjacent_to(symbol).collect();
+            let summary = by_symbol.entry(symbol.symbol).or_default();
+            summary.count += 1;
+            summary.adjacent_numbers += adjacent.len();
+            summary.sum += adjacent.iter().map(|num| num.value).sum::<usize>();
+            if let [first, second] = adjacent[..] {
+                summary.gear_ratio_sum += first.value * second.value;
+            }
+        }
+
+        let mut shared_parts = Vec::new();
+        let mut unattached = Vec::new();
+        for number in schematic.numbers() {
+            let symbols: Vec<_> = schematic.symbols_adjacent_to(number).collect();
+            match symbols.len() {
+                0 => unattached.push(number),
+                1 => {}
+                _ => shared_parts.push((number, symbols)),
+            }
+        }
+
+        Self {
+            by_symbol,
+            shared_parts,
+            unattached,
+        }
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<6} {:>5} {:>7} {:>8} {:>11}",
+            "symbol", "count", "numbers", "sum", "gear ratios"
+        )?;
+        for (symbol, summary) in &self.by_symbol {
+            writeln!(
+                f,
+                "{:<6} {:>5} {:>7} {:>8} {:>11}",
+                symbol,
+                summary.count,
+                summary.adjacent_numbers,
+                summary.sum,
+                summary.gear_ratio_sum
+            )?;
+        }
+
+        writeln!(f, "part numbers touching several symbols:")?;
+        for (number, symbols) in &self.shared_parts {
+            let symbols: Vec<_> = symbols
+                .iter()
+                .map(|s| format!("{} at {}:{}", s.symbol, s.line_num + 1, s.column + 1))
+                .collect();
+            writeln!(
+                f,
+                "{} at {}:{} touches {}",
+                number.value,
+                number.line_num + 1,
+                number.range.start + 1,
+                symbols.join(", ")
+            )?;
+        }
+
+        writeln!(f, "numbers touching no symbol:")?;
+        for number in &self.unattached {
+            writeln!(
+                f,
+                "{} at {}:{}",
+                number.value,
+                number.line_num + 1,
+                number.range.start + 1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let schematic: Schematic = include_str!("../test_data.txt").parse().unwrap();
+        let report = Report::new(&schematic);
+
+        assert_eq!(report.by_symbol.keys().collect::<String>(), "#$*+");
+        assert_eq!(
+            report.by_symbol[&'*'],
+            SymbolSummary {
+                count: 3,
+                adjacent_numbers: 5,
+                sum: 467 + 35 + 617 + 755 + 598,
+                gear_ratio_sum: 467835,
+            }
+        );
+        let total: usize = report.by_symbol.values().map(|s| s.sum).sum();
+        assert_eq!(total, 4361);
+
+        assert!(report.shared_parts.is_empty());
+        let unattached: Vec<_> = report.unattached.iter().map(|n| n.value).collect();
+        assert_eq!(unattached, vec![114, 58]);
+    }
+
+    #[test]
+    fn test_shared_parts() {
+        let schematic: Schematic = "*..\n.12\n..#".parse().unwrap();
+        let report = Report::new(&schematic);
+
+        assert_eq!(report.shared_parts.len(), 1);
+        let (number, symbols) = &report.shared_parts[0];
+        assert_eq!(number.value, 12);
+        let symbols: String = symbols.iter().map(|s| s.symbol).collect();
+        assert_eq!(symbols, "*#");
+        assert_eq!(report.by_symbol[&'#'].sum, 12);
+        assert!(report
+            .to_string()
+            .contains("12 at 2:2 touches * at 1:1, # at 3:3"));
+    }
+}
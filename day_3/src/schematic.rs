@@ -0,0 +1,237 @@
+use std::io::{self, BufRead};
+use std::ops::Range;
+use std::str::FromStr;
+
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub line_num: usize,
+    pub column: usize,
+    pub symbol: char,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub line_num: usize,
+    pub range: Range<usize>,
+    pub value: usize,
+}
+
+/// Numbers and symbols of an engine schematic, indexed by row so that the
+/// neighbours of a cell are found with a binary search on at most three rows.
+#[derive(Debug)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    // Per row, indices into `numbers`/`symbols` in left-to-right order.
+    number_rows: Vec<Vec<usize>>,
+    symbol_rows: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    #[tracing::instrument(name = "parse", skip_all)]
+    pub fn parse(reader: impl BufRead) -> io::Result<Self> {
+        let num_regex = Regex::new(r"([0-9]+)").unwrap();
+        // Anything but letters, digits, periods and whitespace is a symbol.
+        let symbol_regex = Regex::new(r"([^A-Za-z0-9.\s])").unwrap();
+
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        let mut number_rows = Vec::new();
+        let mut symbol_rows = Vec::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut number_row = Vec::new();
+            for m in num_regex.find_iter(&line) {
+                number_row.push(numbers.len());
+                numbers.push(Number {
+                    line_num: index,
+                    range: m.range(),
+                    value: m.as_str().parse().map_err(|e| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("line {}: {}: {}", index + 1, m.as_str(), e),
+                        )
+                    })?,
+                });
+            }
+            number_rows.push(number_row);
+
+            let mut symbol_row = Vec::new();
+            for sym in symbol_regex.find_iter(&line) {
+                symbol_row.push(symbols.len());
+                symbols.push(Symbol {
+                    line_num: index,
+                    column: sym.start(),
+                    symbol: sym.as_str().chars().next().unwrap(),
+                });
+            }
+            symbol_rows.push(symbol_row);
+        }
+
+        Ok(Self {
+            numbers,
+            symbols,
+            number_rows,
+            symbol_rows,
+        })
+    }
+
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    fn neighbouring_rows(line_num: usize) -> Range<usize> {
+        line_num.saturating_sub(1)..line_num + 2
+    }
+
+    pub fn numbers_adjacent_to<'a>(&'a self, symbol: &Symbol) -> impl Iterator<Item = &'a Number> {
+        let columns = symbol.column.saturating_sub(1)..symbol.column + 2;
+        Self::neighbouring_rows(symbol.line_num)
+            .filter_map(|row| self.number_rows.get(row))
+            .flat_map(move |row| {
+                let first = row.partition_point(|&i| self.numbers[i].range.end <= columns.start);
+                let columns = columns.clone();
+                row[first..]
+                    .iter()
+                    .map(|&i| &self.numbers[i])
+                    .take_while(move |num| num.range.start < columns.end)
+            })
+    }
+
+    pub fn symbols_adjacent_to<'a>(&'a self, number: &Number) -> impl Iterator<Item = &'a Symbol> {
+        let columns = number.range.start.saturating_sub(1)..number.range.end + 1;
+        Self::neighbouring_rows(number.line_num)
+            .filter_map(|row| self.symbol_rows.get(row))
+            .flat_map(move |row| {
+                let first = row.partition_point(|&i| self.symbols[i].column < columns.start);
+                let columns = columns.clone();
+                row[first..]
+                    .iter()
+                    .map(|&i| &self.symbols[i])
+                    .take_while(move |sym| columns.contains(&sym.column))
+            })
+    }
+
+    /// Part numbers: numbers adjacent to at least one symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(|num| self.symbols_adjacent_to(num).next().is_some())
+    }
+
+    /// Symbols with exactly `neighbours` adjacent numbers, along with those
+    /// numbers.
+    pub fn gears(&self, neighbours: usize) -> impl Iterator<Item = (&Symbol, Vec<&Number>)> {
+        self.symbols.iter().filter_map(move |symbol| {
+            let adjacent: Vec<_> = self.numbers_adjacent_to(symbol).collect();
+            (adjacent.len() == neighbours).then_some((symbol, adjacent))
+        })
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_adjacency() {
+        let schematic: Schematic = include_str!("../test_data.txt").parse().unwrap();
+
+        let star = &schematic.symbols()[0];
+        assert_eq!(
+            star,
+            &Symbol {
+                line_num: 1,
+                column: 3,
+                symbol: '*',
+            }
+        );
+        let values: Vec<_> = schematic
+            .numbers_adjacent_to(star)
+            .map(|num| num.value)
+            .collect();
+        assert_eq!(values, vec![467, 35]);
+
+        let values: Vec<_> = schematic.numbers().iter().map(|num| num.value).collect();
+        let not_parts: Vec<_> = schematic
+            .numbers()
+            .iter()
+            .filter(|num| schematic.symbols_adjacent_to(num).next().is_none())
+            .map(|num| num.value)
+            .collect();
+        assert_eq!(not_parts, vec![114, 58]);
+        assert_eq!(
+            schematic.part_numbers().count() + not_parts.len(),
+            values.len()
+        );
+
+        let gears: Vec<_> = schematic.gears(2).map(|(sym, _)| sym.line_num).collect();
+        assert_eq!(gears, vec![1, 8]);
+        assert_eq!(schematic.gears(1).count(), 4);
+    }
+
+    #[test]
+    fn test_symbol_class() {
+        let schematic: Schematic = "1[2]3^4_5`6a7Z8.9\t0§".parse().unwrap();
+        let symbols: String = schematic.symbols().iter().map(|s| s.symbol).collect();
+        assert_eq!(symbols, "[]^_`§");
+        assert_eq!(schematic.symbols()[5].column, 19);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = "1.\n..99999999999999999999999"
+            .parse::<Schematic>()
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error
+            .to_string()
+            .starts_with("line 2: 99999999999999999999999: "));
+
+        let error = Schematic::parse(&b"12*\n\xff\n"[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_adjacency_matches_brute_force() {
+        let schematic: Schematic = include_str!("../input.txt").parse().unwrap();
+        for symbol in schematic.symbols() {
+            let expected: Vec<_> = schematic
+                .numbers()
+                .iter()
+                .filter(|num| {
+                    num.line_num + 1 >= symbol.line_num
+                        && num.line_num <= symbol.line_num + 1
+                        && num.range.start <= symbol.column + 1
+                        && symbol.column <= num.range.end
+                })
+                .collect();
+            let actual: Vec<_> = schematic.numbers_adjacent_to(symbol).collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = input.parse::<Schematic>();
+        }
+    }
+}
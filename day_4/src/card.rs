@@ -0,0 +1,132 @@
+use std::str::FromStr;
+
+/// A scratchcard with both sides stored as bitsets, so counting matches is a
+/// single `AND`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    winning_numbers: u128,
+    numbers: u128,
+}
+
+impl Card {
+    fn parse_side(side: &str) -> Result<u128, String> {
+        let mut numbers = 0;
+        for number in side.split_whitespace() {
+            let number = number
+                .parse::<u32>()
+                .map_err(|e| format!("Not a number {:?}: {}", number, e))?;
+            if number >= u128::BITS {
+                return Err(format!("Number out of range: {}", number));
+            }
+            numbers |= 1 << number;
+        }
+        Ok(numbers)
+    }
+
+    #[tracing::instrument(name = "parse", skip_all)]
+    pub fn parse_all<S: AsRef<str>>(lines: &[S]) -> Result<Vec<Self>, String> {
+        lines
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|line| !line.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    pub fn matches(&self) -> usize {
+        (self.winning_numbers & self.numbers).count_ones() as usize
+    }
+
+    /// The winning numbers found among the numbers you have, in ascending order.
+    pub fn matched_numbers(&self) -> Vec<u32> {
+        let matched = self.winning_numbers & self.numbers;
+        (0..u128::BITS)
+            .filter(|n| matched & (1 << n) != 0)
+            .collect()
+    }
+
+    /// The first match makes the card worth one point and each match after the first doubles the point value of that card.
+    ///
+    /// `None` past 32 matches, where the points no longer fit in a `u32`.
+    pub fn points(&self) -> Option<u32> {
+        match self.matches() {
+            0 => Some(0),
+            matches => 1u32.checked_shl(matches as u32 - 1),
+        }
+    }
+}
+
+impl FromStr for Card {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (card, sides) = line
+            .split_once(':')
+            .ok_or_else(|| format!("No card id: {}", line))?;
+        let id = card
+            .strip_prefix("Card")
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or_else(|| format!("Invalid card id: {}", card))?;
+        let (winning_numbers, numbers) = sides
+            .split_once('|')
+            .ok_or_else(|| format!("No numbers: {}", line))?;
+        Ok(Self {
+            id,
+            winning_numbers: Self::parse_side(winning_numbers)?,
+            numbers: Self::parse_side(numbers)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let card: Card = "Card   3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1"
+            .parse()
+            .unwrap();
+        assert_eq!(card.id, 3);
+        assert_eq!(card.matches(), 2);
+        assert_eq!(card.matched_numbers(), vec![1, 21]);
+        assert_eq!(card.points(), Some(2));
+
+        assert!("Card 1: 41 48 | 83 x".parse::<Card>().is_err());
+        assert!("Card 1: 41 48 | 83 128".parse::<Card>().is_err());
+        assert!("Card 1: 41 48 83".parse::<Card>().is_err());
+        assert!("41 48 | 83".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn test_points_overflow() {
+        let numbers = |count: u32| {
+            (0..count)
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let card = |count| format!("Card 1: {} | {}", numbers(count), numbers(count));
+
+        let card_32: Card = card(32).parse().unwrap();
+        assert_eq!(card_32.points(), Some(1 << 31));
+        let card_40: Card = card(40).parse().unwrap();
+        assert_eq!(card_40.matches(), 40);
+        assert_eq!(card_40.points(), None);
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = Card::parse_all(&input.lines().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "(Card *[0-9]{0,4}:( *[0-9]{1,3})* \\|( *[0-9]{1,3})*\n?){0,4}") {
+            let _ = Card::parse_all(&input.lines().collect::<Vec<_>>());
+        }
+    }
+}
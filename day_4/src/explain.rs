@@ -0,0 +1,216 @@
+use std::{fmt, io, str::FromStr};
+
+use serde::Serialize;
+
+use crate::{card::Card, part_two::PartTwo};
+
+/// Copies of a card won from an earlier card.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Received {
+    pub card: u32,
+    pub copies: u32,
+}
+
+/// What a single card contributed to both answers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CardBreakdown {
+    pub id: u32,
+    pub matched: Vec<u32>,
+    pub points: u32,
+    /// Copies held once all winnings are processed, the original included.
+    pub copies: u32,
+    pub received_from: Vec<Received>,
+}
+
+/// What every card contributed, or an error for a card whose points or
+/// copies do not fit in a `u32`.
+pub fn breakdown(cards: &[Card]) -> Result<Vec<CardBreakdown>, String> {
+    let copies = PartTwo::new(cards).copies()?;
+    let mut received_from = vec![Vec::new(); cards.len()];
+    for (index, card) in cards.iter().enumerate() {
+        for received in received_from
+            .iter_mut()
+            .skip(index + 1)
+            .take(card.matches())
+        {
+            received.push(Received {
+                card: card.id,
+                copies: copies[index],
+            });
+        }
+    }
+
+    cards
+        .iter()
+        .zip(copies)
+        .zip(received_from)
+        .map(|((card, copies), received_from)| {
+            Ok(CardBreakdown {
+                id: card.id,
+                matched: card.matched_numbers(),
+                points: card
+                    .points()
+                    .ok_or_else(|| format!("Card {}: the points overflow a u32", card.id))?,
+                copies,
+                received_from,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "Unknown format {:?}, expected table, csv or json",
+                s
+            )),
+        }
+    }
+}
+
+struct Numbers<'a>(&'a [u32]);
+
+impl fmt::Display for Numbers<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numbers: Vec<_> = self.0.iter().map(u32::to_string).collect();
+        write!(f, "{}", numbers.join(" "))
+    }
+}
+
+struct ReceivedFrom<'a>(&'a [Received]);
+
+impl fmt::Display for ReceivedFrom<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let received: Vec<_> = self
+            .0
+            .iter()
+            .map(|r| format!("{}x{}", r.card, r.copies))
+            .collect();
+        write!(f, "{}", received.join(" "))
+    }
+}
+
+pub fn write(
+    breakdown: &[CardBreakdown],
+    format: Format,
+    mut writer: impl io::Write,
+) -> io::Result<()> {
+    match format {
+        Format::Table => {
+            writeln!(
+                writer,
+                "{:>5} {:<30} {:>6} {:>8}  received from",
+                "card", "matched", "points", "copies"
+            )?;
+            for card in breakdown {
+                writeln!(
+                    writer,
+                    "{:>5} {:<30} {:>6} {:>8}  {}",
+                    card.id,
+                    Numbers(&card.matched).to_string(),
+                    card.points,
+                    card.copies,
+                    ReceivedFrom(&card.received_from)
+                )?;
+            }
+        }
+        Format::Csv => {
+            writeln!(writer, "card,matched,points,copies,received_from")?;
+            for card in breakdown {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{}",
+                    card.id,
+                    Numbers(&card.matched),
+                    card.points,
+                    card.copies,
+                    ReceivedFrom(&card.received_from)
+                )?;
+            }
+        }
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut writer, breakdown)?;
+            writeln!(writer)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::file_reader;
+
+    use super::*;
+    use std::io::BufRead;
+
+    fn test_breakdown() -> Vec<CardBreakdown> {
+        let buf_reader = file_reader("./test_data.txt").unwrap();
+        let lines: Vec<String> = buf_reader.lines().map(|l| l.unwrap_or_default()).collect();
+        let cards = Card::parse_all(&lines).unwrap();
+        breakdown(&cards).unwrap()
+    }
+
+    #[test]
+    fn test_breakdown_totals() {
+        let breakdown = test_breakdown();
+        assert_eq!(breakdown.iter().map(|c| c.points).sum::<u32>(), 13);
+        assert_eq!(breakdown.iter().map(|c| c.copies).sum::<u32>(), 30);
+        for card in &breakdown {
+            let received: u32 = card.received_from.iter().map(|r| r.copies).sum();
+            assert_eq!(received + 1, card.copies);
+        }
+
+        assert_eq!(
+            breakdown[3],
+            CardBreakdown {
+                id: 4,
+                matched: vec![84],
+                points: 1,
+                copies: 8,
+                received_from: vec![
+                    Received { card: 1, copies: 1 },
+                    Received { card: 2, copies: 2 },
+                    Received { card: 3, copies: 4 },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let breakdown = test_breakdown();
+
+        let mut csv = Vec::new();
+        write(&breakdown, Format::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("card,matched,points,copies,received_from")
+        );
+        assert_eq!(lines.next(), Some("1,17 48 83 86,8,1,"));
+        assert_eq!(lines.nth(2), Some("4,84,1,8,1x1 2x2 3x4"));
+
+        let mut json = Vec::new();
+        write(&breakdown, Format::Json, &mut json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json[1]["received_from"][0]["card"], 1);
+        assert_eq!(json[5]["matched"], serde_json::json!([]));
+
+        assert_eq!("table".parse(), Ok(Format::Table));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -1,13 +1,43 @@
-use day_3::{file_reader, part_one::PartOne, part_two::PartTwo};
-use std::io::BufRead;
+use day_4::{
+    card::Card,
+    explain::{self, Format},
+    file_reader,
+    part_one::PartOne,
+    part_two::PartTwo,
+};
+use std::{
+    env,
+    io::{self, BufRead},
+};
 
-fn main() {
-    let buf_reader = file_reader("./input.txt");
-    let lines: Vec<String> = buf_reader.lines().map(|l| l.unwrap_or_default()).collect();
+fn main() -> io::Result<()> {
+    let buf_reader = file_reader("./input.txt")?;
+    let lines = buf_reader.lines().collect::<io::Result<Vec<String>>>()?;
+    let cards =
+        Card::parse_all(&lines).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
-    let part_one = PartOne::new(&lines).run();
+    // `--explain <table|csv|json>` prints the per-card breakdown instead.
+    let mut args = env::args().skip(1);
+    if let Some("--explain") = args.next().as_deref() {
+        let format = args
+            .next()
+            .unwrap_or_else(|| "table".to_string())
+            .parse::<Format>()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let breakdown = explain::breakdown(&cards)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        return explain::write(&breakdown, format, io::stdout().lock());
+    }
+
+    let part_one = PartOne::new(&cards)
+        .run()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
     println!("Part One: {}", part_one);
 
-    let part_two = PartTwo::new(&lines).run();
+    let part_two = PartTwo::new(&cards)
+        .run()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
     println!("Part Two: {}", part_two);
+
+    Ok(())
 }
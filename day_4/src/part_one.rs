@@ -34,104 +34,124 @@ So, in this example, the Elf's pile of scratchcards is worth 13 points.
 Take a seat in the large pile of colorful cards. How many points are they worth in total?
 */
 
-#[derive(Debug)]
-struct Card {
-    numbers: Vec<u32>,
-    winning_numbers: Vec<u32>,
-}
-
-impl Card {
-    pub fn new(numbers: Vec<u32>, winning_numbers: Vec<u32>) -> Self {
-        Self {
-            winning_numbers,
-            numbers,
-        }
-    }
-}
+use crate::card::Card;
 
-pub struct PartOne {
-    cards: Vec<Card>,
+pub struct PartOne<'a> {
+    cards: &'a [Card],
 }
 
-impl PartOne {
-    fn parse_side(side: &str) -> Vec<u32> {
-        let mut numbers: Vec<u32> = Vec::new();
-        let split_side = side.split(" ").collect::<Vec<&str>>();
-        for number in split_side {
-            let number = number.trim();
-            if number.is_empty() {
-                continue;
-            }
-            numbers.push(number.parse::<u32>().expect("Not a number"));
-        }
-        numbers
-    }
-
-    fn parse_lines(lines: &Vec<String>) -> Vec<Card> {
-        let mut cards: Vec<Card> = Vec::new();
-        for line in lines {
-            // Split by colon to get the right side
-            let split_line = line.split(":").collect::<Vec<&str>>();
-            let right_side = split_line.get(1).expect("No right side").trim();
-            // Split by pipe to get the winning numbers and the numbers
-            let split_right_side = right_side.split("|").collect::<Vec<&str>>();
-            let left_side = split_right_side.get(0).expect("No left side");
-            let right_side = split_right_side.get(1).expect("No right side");
-
-            let numbers = Self::parse_side(right_side);
-            let winning_numbers = Self::parse_side(left_side);
-            cards.push(Card::new(numbers, winning_numbers));
-        }
-        cards
-    }
-
-    pub fn new(lines: &Vec<String>) -> Self {
-        let cards = Self::parse_lines(lines);
+impl<'a> PartOne<'a> {
+    pub fn new(cards: &'a [Card]) -> Self {
         Self { cards }
     }
 
-    /**
-     * The first match makes the card worth one point and each match after the first doubles the point value of that card.
-     */
-    fn calculate_points(&self, card: &Card) -> u32 {
-        println!("{:?}", card);
-        let mut points = 0;
-        let mut should_double = false;
-        card.numbers.iter().for_each(|n| {
-            if card.winning_numbers.contains(n) {
-                if should_double {
-                    points *= 2;
-                } else {
-                    points += 1;
-                }
-                should_double = true;
-            }
-        });
-        points
+    pub fn run(&self) -> Result<u32, String> {
+        self.cards.iter().try_fold(0u32, |sum, card| {
+            card.points()
+                .and_then(|points| sum.checked_add(points))
+                .ok_or_else(|| format!("Card {}: the points overflow a u32", card.id))
+        })
     }
+}
 
-    pub fn run(&self) -> u32 {
-        let mut points = 0;
-        for card in &self.cards {
-            points += self.calculate_points(card);
-        }
-        points
-    }
+#[tracing::instrument(skip(input))]
+pub fn run(input: &str) -> Result<u32, String> {
+    let cards = Card::parse_all(&input.lines().collect::<Vec<_>>())?;
+    PartOne::new(&cards).run()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::file_reader;
+    use proptest::prelude::*;
 
     use super::*;
     use std::io::BufRead;
 
     #[test]
     fn test_part_one() {
-        let buf_reader = file_reader("./test_data.txt");
+        let buf_reader = file_reader("./test_data.txt").unwrap();
         let lines: Vec<String> = buf_reader.lines().map(|l| l.unwrap_or_default()).collect();
-        let result = PartOne::new(&lines).run();
+        let cards = Card::parse_all(&lines).unwrap();
+        let result = PartOne::new(&cards).run();
+
+        assert_eq!(result, Ok(13));
+    }
+
+    #[test]
+    fn test_points_overflow() {
+        let numbers = (0..40).map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+        let card = format!("Card 1: {} | {}", numbers, numbers);
+        let cards = Card::parse_all(&[&card]).unwrap();
+        assert_eq!(
+            PartOne::new(&cards).run(),
+            Err("Card 1: the points overflow a u32".to_string())
+        );
+    }
+
+    /// A pile of cards with both sides drawn from a small range, so that
+    /// matches are common but the copies of part two cannot overflow.
+    fn cards() -> impl Strategy<Value = String> {
+        let side = || prop::collection::btree_set(1..30u32, 0..6);
+        prop::collection::vec((side(), side()), 1..=16).prop_map(|cards| {
+            cards
+                .iter()
+                .enumerate()
+                .map(|(index, (winning, numbers))| {
+                    let side = |numbers: &std::collections::BTreeSet<u32>| {
+                        numbers
+                            .iter()
+                            .map(|n| format!("{:>2}", n))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    };
+                    format!(
+                        "Card {:>3}: {} | {}",
+                        index + 1,
+                        side(winning),
+                        side(numbers)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
 
-        assert_eq!(result, 13);
+    /// The number of winning numbers on each card, counted number by number.
+    fn matches(input: &str) -> Vec<usize> {
+        input
+            .lines()
+            .map(|line| {
+                let (_, sides) = line.split_once(':').unwrap();
+                let (winning, numbers) = sides.split_once('|').unwrap();
+                let winning: Vec<&str> = winning.split_whitespace().collect();
+                numbers
+                    .split_whitespace()
+                    .filter(|number| winning.contains(number))
+                    .count()
+            })
+            .collect()
+    }
+
+    /// Doubles a point for every match after the first.
+    fn reference(input: &str) -> u32 {
+        matches(input)
+            .iter()
+            .map(|&matches| {
+                if matches == 0 {
+                    0
+                } else {
+                    2u32.pow(matches as u32 - 1)
+                }
+            })
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in cards()) {
+            let cards = Card::parse_all(&input.lines().collect::<Vec<_>>()).unwrap();
+            prop_assert_eq!(PartOne::new(&cards).run(), Ok(reference(&input)));
+        }
     }
 }
@@ -27,103 +27,171 @@ Once all of the originals and copies have been processed, you end up with 1 inst
 
 Process all of the original and copied scratchcards until no more scratchcards are won. Including the original set of scratchcards, how many total scratchcards do you end up with?
 */
-#[derive(Debug)]
-struct Card {
-    numbers: Vec<u32>,
-    winning_numbers: Vec<u32>,
-}
-
-impl Card {
-    pub fn new(numbers: Vec<u32>, winning_numbers: Vec<u32>) -> Self {
-        Self {
-            winning_numbers,
-            numbers,
-        }
-    }
-}
+use crate::card::Card;
 
-pub struct PartTwo {
-    cards: Vec<Card>,
+pub struct PartTwo<'a> {
+    cards: &'a [Card],
 }
 
-impl PartTwo {
-    fn parse_side(side: &str) -> Vec<u32> {
-        let mut numbers: Vec<u32> = Vec::new();
-        let split_side = side.split(" ").collect::<Vec<&str>>();
-        for number in split_side {
-            let number = number.trim();
-            if number.is_empty() {
-                continue;
-            }
-            numbers.push(number.parse::<u32>().expect("Not a number"));
-        }
-        numbers
-    }
-
-    fn parse_lines(lines: &Vec<String>) -> Vec<Card> {
-        let mut cards: Vec<Card> = Vec::new();
-        for line in lines {
-            // Split by colon to get the right side
-            let split_line = line.split(":").collect::<Vec<&str>>();
-            let right_side = split_line.get(1).expect("No right side").trim();
-            // Split by pipe to get the winning numbers and the numbers
-            let split_right_side = right_side.split("|").collect::<Vec<&str>>();
-            let left_side = split_right_side.get(0).expect("No left side");
-            let right_side = split_right_side.get(1).expect("No right side");
-
-            let numbers = Self::parse_side(right_side);
-            let winning_numbers = Self::parse_side(left_side);
-            cards.push(Card::new(numbers, winning_numbers));
-        }
-        cards
-    }
-
-    pub fn new(lines: &Vec<String>) -> Self {
-        let cards = Self::parse_lines(lines);
+impl<'a> PartTwo<'a> {
+    pub fn new(cards: &'a [Card]) -> Self {
         Self { cards }
     }
 
-    pub fn run(&self) -> u32 {
-        let mut scratchcard_counts = vec![1; self.cards.len()]; // Initialize with 1 for each original card
+    /// Number of copies held of each card, original included, or an error
+    /// for the first card whose copies do not fit in a `u32`.
+    ///
+    /// A card's copies all win the same following cards, so instead of
+    /// replaying every copy the wins are added to a difference array whose
+    /// running sum is the number of copies won by each later card.
+    pub fn copies(&self) -> Result<Vec<u32>, String> {
+        let mut won = vec![0i64; self.cards.len() + 1];
+        let mut running = 0;
+        let mut copies = Vec::with_capacity(self.cards.len());
 
         for (index, card) in self.cards.iter().enumerate() {
-            let mut new_wins = vec![0; self.cards.len()];
-
-            for _ in 0..scratchcard_counts[index] {
-                let matches = card
-                    .winning_numbers
-                    .iter()
-                    .filter(|&n| card.numbers.contains(n))
-                    .count();
-                for win_index in index + 1..index + 1 + matches {
-                    if win_index < self.cards.len() {
-                        new_wins[win_index] += 1;
-                    }
-                }
-            }
-
-            for (i, &wins) in new_wins.iter().enumerate() {
-                scratchcard_counts[i] += wins;
+            running += won[index];
+            let count = 1 + running;
+            copies.push(
+                u32::try_from(count)
+                    .map_err(|_| format!("Card {}: the copies overflow a u32", card.id))?,
+            );
+
+            let last = (index + card.matches()).min(self.cards.len() - 1);
+            if last > index {
+                won[index + 1] += count;
+                won[last + 1] -= count;
             }
         }
 
-        scratchcard_counts.iter().sum()
+        Ok(copies)
+    }
+
+    pub fn run(&self) -> Result<u32, String> {
+        self.cards
+            .iter()
+            .zip(self.copies()?)
+            .try_fold(0u32, |sum, (card, copies)| {
+                sum.checked_add(copies).ok_or_else(|| {
+                    format!("Card {}: the total of the copies overflows a u32", card.id)
+                })
+            })
     }
 }
 
+#[tracing::instrument(skip(input))]
+pub fn run(input: &str) -> Result<u32, String> {
+    let cards = Card::parse_all(&input.lines().collect::<Vec<_>>())?;
+    PartTwo::new(&cards).run()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::file_reader;
+    use proptest::prelude::*;
 
     use super::*;
     use std::io::BufRead;
 
     #[test]
     fn test_part_two() {
-        let buf_reader = file_reader("./test_data.txt");
+        let buf_reader = file_reader("./test_data.txt").unwrap();
         let lines: Vec<String> = buf_reader.lines().map(|l| l.unwrap_or_default()).collect();
-        let result = PartTwo::new(&lines).run();
+        let cards = Card::parse_all(&lines).unwrap();
+        let part_two = PartTwo::new(&cards);
+
+        assert_eq!(part_two.copies(), Ok(vec![1, 2, 4, 8, 14, 1]));
+        assert_eq!(part_two.run(), Ok(30));
+    }
+
+    #[test]
+    fn test_copies_overflow() {
+        // Every card wins all the cards after it, so card `n` ends up with
+        // `2^(n - 1)` copies.
+        let numbers = (1..=40)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let lines: Vec<String> = (1..=33)
+            .map(|id| format!("Card {}: {} | {}", id, numbers, numbers))
+            .collect();
+        let cards = Card::parse_all(&lines).unwrap();
+        assert_eq!(PartTwo::new(&cards[..32]).run(), Ok(u32::MAX));
+        assert_eq!(
+            PartTwo::new(&cards).run(),
+            Err("Card 33: the copies overflow a u32".to_string())
+        );
+
+        // One more card that wins nothing tips the total over.
+        let mut cards = cards[..32].to_vec();
+        cards.insert(0, Card::parse_all(&["Card 0: 1 | 2"]).unwrap().remove(0));
+        assert_eq!(
+            PartTwo::new(&cards).run(),
+            Err("Card 32: the total of the copies overflows a u32".to_string())
+        );
+    }
+
+    /// A pile of cards with both sides drawn from a small range, so that
+    /// matches are common but the copies of part two cannot overflow.
+    fn cards() -> impl Strategy<Value = String> {
+        let side = || prop::collection::btree_set(1..30u32, 0..6);
+        prop::collection::vec((side(), side()), 1..=16).prop_map(|cards| {
+            cards
+                .iter()
+                .enumerate()
+                .map(|(index, (winning, numbers))| {
+                    let side = |numbers: &std::collections::BTreeSet<u32>| {
+                        numbers
+                            .iter()
+                            .map(|n| format!("{:>2}", n))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    };
+                    format!(
+                        "Card {:>3}: {} | {}",
+                        index + 1,
+                        side(winning),
+                        side(numbers)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    /// The number of winning numbers on each card, counted number by number.
+    fn matches(input: &str) -> Vec<usize> {
+        input
+            .lines()
+            .map(|line| {
+                let (_, sides) = line.split_once(':').unwrap();
+                let (winning, numbers) = sides.split_once('|').unwrap();
+                let winning: Vec<&str> = winning.split_whitespace().collect();
+                numbers
+                    .split_whitespace()
+                    .filter(|number| winning.contains(number))
+                    .count()
+            })
+            .collect()
+    }
+
+    /// Hands out the copies won by each card one card at a time.
+    fn reference(input: &str) -> u32 {
+        let matches = matches(input);
+        let mut copies = vec![1u32; matches.len()];
+        for (index, &matches) in matches.iter().enumerate() {
+            for won in index + 1..(index + 1 + matches).min(copies.len()) {
+                copies[won] += copies[index];
+            }
+        }
+        copies.iter().sum()
+    }
 
-        assert_eq!(result, 30);
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in cards()) {
+            let cards = Card::parse_all(&input.lines().collect::<Vec<_>>()).unwrap();
+            prop_assert_eq!(PartTwo::new(&cards).run(), Ok(reference(&input)));
+        }
     }
 }
@@ -0,0 +1,688 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    ops::Range,
+    str::FromStr,
+};
+
+/// A `destination source length` line of a map, with its 1-based line
+/// number in the almanac.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapEntry {
+    pub destination: i64,
+    pub source: i64,
+    pub length: i64,
+    pub line: usize,
+}
+
+impl MapEntry {
+    fn parse(input: &str, line: usize) -> Result<Self, String> {
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        let [destination, source, length] = parts[..] else {
+            return Err(format!("line {}: expected three numbers", line));
+        };
+        let parse = |value: &str| {
+            value
+                .parse::<i64>()
+                .map_err(|e| format!("line {}: {:?}: {}", line, value, e))
+        };
+        Ok(Self {
+            destination: parse(destination)?,
+            source: parse(source)?,
+            length: parse(length)?,
+            line,
+        })
+    }
+}
+
+/// A problem found in a map entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    /// The range covers no values.
+    EmptyRange { line: usize, length: i64 },
+    /// `source + length`, `destination + length` or `destination - source`
+    /// does not fit in an `i64`.
+    Overflow { line: usize },
+    /// The source range shares values with the entry on an earlier line.
+    Overlap { line: usize, earlier: usize },
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::EmptyRange { line, length } => {
+                write!(f, "line {}: range length {} is not positive", line, length)
+            }
+            Lint::Overflow { line } => write!(f, "line {}: range overflows i64", line),
+            Lint::Overlap { line, earlier } => write!(
+                f,
+                "line {}: source range overlaps the one on line {}",
+                line, earlier
+            ),
+        }
+    }
+}
+
+/// What to do when source ranges of a map overlap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Refuse to build the map.
+    #[default]
+    Reject,
+    /// The entry that comes first in the map wins.
+    FirstWins,
+}
+
+/// A piece of a piecewise-linear function: every value in `start..end` is
+/// shifted by `offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub start: i64,
+    pub end: i64,
+    pub offset: i64,
+}
+
+/// A function that shifts values by a constant per segment and leaves values
+/// outside of every segment unchanged. Segments are sorted and disjoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PiecewiseLinear {
+    segments: Vec<Segment>,
+}
+
+impl PiecewiseLinear {
+    /// Builds the function of one almanac map. Where source ranges overlap
+    /// the entry that comes first in the map wins. Entries must have passed
+    /// [`CategoryMap::lint`] without overflows.
+    pub fn from_entries(entries: &[MapEntry]) -> Self {
+        let mut segments: Vec<Segment> = Vec::new();
+        for entry in entries {
+            let mut uncovered = vec![Range {
+                start: entry.source,
+                end: entry.source + entry.length,
+            }];
+            for segment in &segments {
+                uncovered = uncovered
+                    .into_iter()
+                    .flat_map(|range| {
+                        [
+                            range.start..range.end.min(segment.start),
+                            range.start.max(segment.end)..range.end,
+                        ]
+                    })
+                    .filter(|range| !range.is_empty())
+                    .collect();
+            }
+            segments.extend(uncovered.into_iter().map(|range| Segment {
+                start: range.start,
+                end: range.end,
+                offset: entry.destination - entry.source,
+            }));
+        }
+        segments.sort_by_key(|segment| segment.start);
+        Self { segments }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Every segment plus the identity gaps between them, covering
+    /// `i64::MIN..i64::MAX`.
+    fn pieces(&self) -> Vec<Segment> {
+        let mut pieces = Vec::with_capacity(self.segments.len() * 2 + 1);
+        let mut position = i64::MIN;
+        for segment in &self.segments {
+            if position < segment.start {
+                pieces.push(Segment {
+                    start: position,
+                    end: segment.start,
+                    offset: 0,
+                });
+            }
+            pieces.push(*segment);
+            position = segment.end;
+        }
+        if position < i64::MAX {
+            pieces.push(Segment {
+                start: position,
+                end: i64::MAX,
+                offset: 0,
+            });
+        }
+        pieces
+    }
+
+    pub fn apply(&self, value: i64) -> i64 {
+        let index = self.segments.partition_point(|s| s.end <= value);
+        match self.segments.get(index) {
+            Some(segment) if segment.start <= value => value + segment.offset,
+            _ => value,
+        }
+    }
+
+    /// The function that applies `self` and then `then`, or an error where
+    /// the two offsets together do not fit in an `i64`.
+    pub fn then(&self, then: &PiecewiseLinear) -> Result<PiecewiseLinear, String> {
+        let next_pieces = then.pieces();
+        let mut segments = Vec::new();
+        for piece in self.pieces() {
+            let image =
+                piece.start.saturating_add(piece.offset)..piece.end.saturating_add(piece.offset);
+            for next in &next_pieces {
+                let start = image.start.max(next.start);
+                let end = image.end.min(next.end);
+                if start >= end {
+                    continue;
+                }
+                let offset = piece.offset.checked_add(next.offset).ok_or_else(|| {
+                    format!(
+                        "values {}..{} are shifted by {} and then {}, which overflows i64",
+                        start - piece.offset,
+                        end - piece.offset,
+                        piece.offset,
+                        next.offset
+                    )
+                })?;
+                if offset != 0 {
+                    segments.push(Segment {
+                        start: start - piece.offset,
+                        end: end - piece.offset,
+                        offset,
+                    });
+                }
+            }
+        }
+        Ok(PiecewiseLinear { segments })
+    }
+
+    /// Every value that maps to `value`, in ascending order.
+    pub fn preimage(&self, value: i64) -> Vec<i64> {
+        self.pieces()
+            .into_iter()
+            .filter_map(|piece| {
+                let source = value.checked_sub(piece.offset)?;
+                (piece.start <= source && source < piece.end).then_some(source)
+            })
+            .collect()
+    }
+
+    /// The smallest value `range` maps to.
+    pub fn min_over(&self, range: Range<i64>) -> Option<i64> {
+        self.pieces()
+            .into_iter()
+            .filter_map(|piece| {
+                let start = range.start.max(piece.start);
+                let end = range.end.min(piece.end);
+                (start < end).then_some(start + piece.offset)
+            })
+            .min()
+    }
+}
+
+/// An `X-to-Y map:` section.
+#[derive(Debug, Clone)]
+pub struct CategoryMap {
+    pub source: String,
+    pub destination: String,
+    pub line: usize,
+    pub entries: Vec<MapEntry>,
+}
+
+impl CategoryMap {
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            if entry.length <= 0 {
+                lints.push(Lint::EmptyRange {
+                    line: entry.line,
+                    length: entry.length,
+                });
+                continue;
+            }
+            let (Some(end), Some(_), Some(_)) = (
+                entry.source.checked_add(entry.length),
+                entry.destination.checked_add(entry.length),
+                entry.destination.checked_sub(entry.source),
+            ) else {
+                lints.push(Lint::Overflow { line: entry.line });
+                continue;
+            };
+            for earlier in &self.entries[..index] {
+                let earlier_end = earlier.source.checked_add(earlier.length);
+                if earlier.length > 0
+                    && earlier_end.is_some_and(|earlier_end| {
+                        earlier.source < end && entry.source < earlier_end
+                    })
+                {
+                    lints.push(Lint::Overlap {
+                        line: entry.line,
+                        earlier: earlier.line,
+                    });
+                }
+            }
+        }
+        lints
+    }
+
+    /// The function of this map, or every lint `policy` does not allow.
+    pub fn function(&self, policy: OverlapPolicy) -> Result<PiecewiseLinear, String> {
+        let errors: Vec<String> = self
+            .lint()
+            .into_iter()
+            .filter(|lint| {
+                !(policy == OverlapPolicy::FirstWins && matches!(lint, Lint::Overlap { .. }))
+            })
+            .map(|lint| lint.to_string())
+            .collect();
+        if !errors.is_empty() {
+            return Err(format!(
+                "{}-to-{} map: {}",
+                self.source,
+                self.destination,
+                errors.join("; ")
+            ));
+        }
+        Ok(PiecewiseLinear::from_entries(&self.entries))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    pub maps: Vec<CategoryMap>,
+    pub policy: OverlapPolicy,
+}
+
+impl Almanac {
+    pub fn with_policy(mut self, policy: OverlapPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Every problem in every map, in the order of the almanac.
+    pub fn lint(&self) -> Vec<Lint> {
+        self.maps.iter().flat_map(CategoryMap::lint).collect()
+    }
+
+    /// The maps leading from `source` to `target`, found by a breadth-first
+    /// search over the categories so the sections may come in any order.
+    pub fn path(&self, source: &str, target: &str) -> Result<Vec<&CategoryMap>, String> {
+        let mut previous: HashMap<&str, &CategoryMap> = HashMap::new();
+        let mut queue = VecDeque::from([source]);
+        while let Some(category) = queue.pop_front() {
+            if category == target {
+                let mut path = Vec::new();
+                let mut current = target;
+                while current != source {
+                    let map = previous[current];
+                    path.push(map);
+                    current = &map.source;
+                }
+                path.reverse();
+                return Ok(path);
+            }
+            for map in self.maps.iter().filter(|map| map.source == category) {
+                if map.destination != source && !previous.contains_key(map.destination.as_str()) {
+                    previous.insert(&map.destination, map);
+                    queue.push_back(&map.destination);
+                }
+            }
+        }
+        Err(format!("No maps lead from {} to {}", source, target))
+    }
+
+    /// All maps from `source` to `target` composed into a single function.
+    pub fn function(&self, source: &str, target: &str) -> Result<PiecewiseLinear, String> {
+        self.path(source, target)?
+            .into_iter()
+            .try_fold(PiecewiseLinear::default(), |function, map| {
+                function.then(&map.function(self.policy)?)
+            })
+    }
+
+    pub fn map(&self, source: &str, target: &str, value: i64) -> Result<i64, String> {
+        Ok(self.function(source, target)?.apply(value))
+    }
+
+    /// The `source` values that map to `value` in `target`, e.g. the seeds
+    /// that produce a location.
+    pub fn preimage(&self, source: &str, target: &str, value: i64) -> Result<Vec<i64>, String> {
+        Ok(self.function(source, target)?.preimage(value))
+    }
+}
+
+impl FromStr for Almanac {
+    type Err = String;
+
+    #[tracing::instrument(name = "parse", skip_all)]
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()));
+
+        let (seeds_line, seeds) = lines
+            .by_ref()
+            .find(|(_, line)| !line.is_empty())
+            .ok_or("Empty almanac")?;
+        let seeds = seeds
+            .strip_prefix("seeds:")
+            .ok_or_else(|| format!("line {}: expected seeds", seeds_line))?
+            .split_whitespace()
+            .map(|seed| {
+                seed.parse()
+                    .map_err(|e| format!("line {}: {:?}: {}", seeds_line, seed, e))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut maps: Vec<CategoryMap> = Vec::new();
+        for (line_number, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_suffix(" map:") {
+                let (source, destination) = header
+                    .split_once("-to-")
+                    .ok_or_else(|| format!("line {}: invalid map header", line_number))?;
+                maps.push(CategoryMap {
+                    source: source.to_string(),
+                    destination: destination.to_string(),
+                    line: line_number,
+                    entries: Vec::new(),
+                });
+                continue;
+            }
+            let map = maps
+                .last_mut()
+                .ok_or_else(|| format!("line {}: map entry before any header", line_number))?;
+            map.entries.push(MapEntry::parse(line, line_number)?);
+        }
+
+        Ok(Self {
+            seeds,
+            maps,
+            policy: OverlapPolicy::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn almanac() -> Almanac {
+        include_str!("../test_data.txt").parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let almanac = almanac();
+        assert_eq!(almanac.seeds, vec![79, 14, 55, 13]);
+        assert_eq!(almanac.maps.len(), 7);
+        assert_eq!(almanac.maps[1].source, "soil");
+        assert_eq!(almanac.maps[1].destination, "fertilizer");
+        assert_eq!(almanac.maps[1].line, 7);
+        assert_eq!(
+            almanac.maps[0].entries[1],
+            MapEntry {
+                destination: 52,
+                source: 50,
+                length: 48,
+                line: 5,
+            }
+        );
+
+        assert!("seeds: 1 x".parse::<Almanac>().is_err());
+        assert_eq!(
+            "\n\nseeds: 1 x".parse::<Almanac>().unwrap_err(),
+            "line 3: \"x\": invalid digit found in string"
+        );
+        assert_eq!(
+            "\nseed: 1".parse::<Almanac>().unwrap_err(),
+            "line 2: expected seeds"
+        );
+        assert!("seeds: 1\n\n1 2 3".parse::<Almanac>().is_err());
+        assert!("seeds: 1\n\nseed-soil map:\n1 2 3"
+            .parse::<Almanac>()
+            .is_err());
+        assert!("seeds: 1\n\na-to-b map:\n1 2".parse::<Almanac>().is_err());
+    }
+
+    #[test]
+    fn test_map() {
+        let almanac = almanac();
+        let locations: Vec<_> = almanac
+            .seeds
+            .iter()
+            .map(|&seed| almanac.map("seed", "location", seed).unwrap())
+            .collect();
+        assert_eq!(locations, vec![82, 43, 86, 35]);
+
+        // Seed 79 has soil 81, fertilizer 81, water 81, light 74,
+        // temperature 78 and humidity 78.
+        assert_eq!(almanac.map("soil", "humidity", 81), Ok(78));
+        assert_eq!(almanac.map("light", "light", 74), Ok(74));
+        assert!(almanac.map("location", "seed", 82).is_err());
+        assert!(almanac.map("seed", "colour", 82).is_err());
+    }
+
+    #[test]
+    fn test_maps_in_any_order() {
+        let input = include_str!("../test_data.txt");
+        let mut sections: Vec<&str> = input.split("\n\n").collect();
+        sections[1..].reverse();
+        let shuffled: Almanac = sections.join("\n\n").parse().unwrap();
+        assert_eq!(shuffled.maps[0].source, "humidity");
+        assert_eq!(shuffled.map("seed", "location", 79), Ok(82));
+    }
+
+    #[test]
+    fn test_composition_matches_chain() {
+        let almanac = almanac();
+        let function = almanac.function("seed", "location").unwrap();
+        let path = almanac.path("seed", "location").unwrap();
+        for seed in -5..120 {
+            let chained = path.iter().fold(seed, |value, map| {
+                map.function(almanac.policy).unwrap().apply(value)
+            });
+            assert_eq!(function.apply(seed), chained, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_preimage() {
+        let almanac = almanac();
+        assert_eq!(almanac.preimage("seed", "location", 82), Ok(vec![79]));
+        for location in 0..120 {
+            let seeds = almanac.preimage("seed", "location", location).unwrap();
+            let expected: Vec<_> = (-5..200)
+                .filter(|&seed| almanac.map("seed", "location", seed) == Ok(location))
+                .collect();
+            assert_eq!(seeds, expected, "location {}", location);
+        }
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(almanac().lint().is_empty());
+
+        let input = "seeds: 1
+
+a-to-b map:
+100 10 5
+200 12 5
+0 30 0
+0 40 -2
+9223372036854775800 50 10
+0 -9223372036854775808 5
+300 9 2";
+        let almanac: Almanac = input.parse().unwrap();
+        let lints = almanac.lint();
+        assert_eq!(
+            lints,
+            vec![
+                Lint::Overlap {
+                    line: 5,
+                    earlier: 4
+                },
+                Lint::EmptyRange { line: 6, length: 0 },
+                Lint::EmptyRange {
+                    line: 7,
+                    length: -2
+                },
+                Lint::Overflow { line: 8 },
+                Lint::Overflow { line: 9 },
+                Lint::Overlap {
+                    line: 10,
+                    earlier: 4
+                },
+            ]
+        );
+        assert_eq!(
+            lints[0].to_string(),
+            "line 5: source range overlaps the one on line 4"
+        );
+
+        let error = almanac.map("a", "b", 12).unwrap_err();
+        assert!(error.starts_with("a-to-b map: line 5: "), "{}", error);
+
+        let input = "seeds: 1\n\nseed-to-location map:\n9223372036854775806 -5 1\n";
+        let almanac: Almanac = input.parse().unwrap();
+        assert_eq!(almanac.lint(), vec![Lint::Overflow { line: 4 }]);
+        assert_eq!(
+            almanac.map("seed", "location", -5),
+            Err("seed-to-location map: line 4: range overflows i64".to_string())
+        );
+    }
+
+    #[test]
+    fn test_composed_offset_overflow() {
+        let input = "seeds: 1
+
+a-to-b map:
+5000000000000000000 -4000000000000000000 10
+
+b-to-c map:
+9000000000000000000 5000000000000000000 10";
+        let almanac: Almanac = input.parse().unwrap();
+        assert!(almanac.lint().is_empty());
+        let error = almanac.function("a", "c").unwrap_err();
+        assert!(error.ends_with("which overflows i64"), "{}", error);
+        assert_eq!(almanac.map("a", "c", -4000000000000000000), Err(error));
+        assert_eq!(
+            almanac.map("a", "b", -4000000000000000000),
+            Ok(5000000000000000000)
+        );
+    }
+
+    #[test]
+    fn test_overlap_policy() {
+        let input = "seeds: 1\n\na-to-b map:\n100 10 5\n200 12 5\n";
+        let almanac: Almanac = input.parse().unwrap();
+        assert_eq!(
+            almanac.map("a", "b", 12),
+            Err("a-to-b map: line 5: source range overlaps the one on line 4".to_string())
+        );
+
+        let almanac = almanac.with_policy(OverlapPolicy::FirstWins);
+        assert_eq!(almanac.map("a", "b", 12), Ok(102));
+
+        let input = "seeds: 1\n\na-to-b map:\n100 10 -5\n";
+        let almanac: Almanac = input.parse().unwrap();
+        let almanac = almanac.with_policy(OverlapPolicy::FirstWins);
+        assert!(almanac.map("a", "b", 12).is_err());
+    }
+
+    #[test]
+    fn test_first_entry_wins() {
+        let entries = [
+            MapEntry::parse("100 10 5", 1).unwrap(),
+            MapEntry::parse("200 12 5", 2).unwrap(),
+        ];
+        let function = PiecewiseLinear::from_entries(&entries);
+        assert_eq!(function.apply(12), 102);
+        assert_eq!(function.apply(15), 203);
+        assert_eq!(function.apply(17), 17);
+    }
+
+    const CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+
+    /// The maps from seed to location, each with source ranges that do not
+    /// overlap, so the default policy accepts them.
+    pub(crate) fn maps() -> impl Strategy<Value = String> {
+        let entry = (0..200i64, 0..20i64, 1..30i64);
+        let map = prop::collection::vec(entry, 0..5).prop_map(|entries| {
+            let mut source = 0;
+            entries
+                .iter()
+                .map(|&(destination, gap, length)| {
+                    source += gap;
+                    let line = format!("{} {} {}", destination, source, length);
+                    source += length;
+                    line
+                })
+                .collect::<Vec<_>>()
+        });
+        prop::collection::vec(map, CATEGORIES.len() - 1).prop_map(|maps| {
+            maps.iter()
+                .zip(CATEGORIES.windows(2))
+                .map(|(entries, names)| {
+                    let mut section = format!("{}-to-{} map:", names[0], names[1]);
+                    for entry in entries {
+                        section.push('\n');
+                        section.push_str(entry);
+                    }
+                    section
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        })
+    }
+
+    /// Walks `seed` through the maps in the order they appear, checking every
+    /// entry of each map in turn.
+    pub(crate) fn locate(maps: &str, seed: i64) -> i64 {
+        maps.split("\n\n").fold(seed, |value, section| {
+            section
+                .lines()
+                .skip(1)
+                .map(|line| {
+                    let numbers: Vec<i64> = line.split(' ').map(|n| n.parse().unwrap()).collect();
+                    (numbers[0], numbers[1], numbers[2])
+                })
+                .find(|&(_, source, length)| (source..source + length).contains(&value))
+                .map_or(value, |(destination, source, _)| {
+                    destination + value - source
+                })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = input.parse::<Almanac>();
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "seeds:( -?[0-9]{1,20}){0,4}(\n\n[a-z]{1,3}-to-[a-z]{1,3} map:(\n-?[0-9]{1,20} -?[0-9]{1,20} -?[0-9]{1,20}){0,3}){0,3}") {
+            let _ = input.parse::<Almanac>();
+        }
+
+        #[test]
+        fn maps_agree_with_reference(maps in maps(), seed in -10..250i64) {
+            let almanac: Almanac = format!("seeds: {}\n\n{}", seed, maps).parse().unwrap();
+            prop_assert_eq!(almanac.map("seed", "location", seed), Ok(locate(&maps, seed)));
+        }
+    }
+}
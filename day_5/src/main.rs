@@ -1,11 +1,16 @@
-use day_3::part_one::PartOne;
-use day_3::part_two::PartTwo;
+use std::env;
+
+use day_5::almanac::{Almanac, OverlapPolicy};
+use day_5::part_one::PartOne;
+use day_5::part_two::PartTwo;
 use dotenv::dotenv;
-use log::info;
+use tracing::{info, warn};
 
 fn setup() {
     dotenv().ok();
-    env_logger::init();
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
 }
 
 fn main() {
@@ -13,9 +18,23 @@ fn main() {
 
     let string = include_str!("../input.txt");
 
-    let part_one = PartOne::new(string).run();
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--lint") {
+        let almanac: Almanac = string.parse().unwrap();
+        for lint in almanac.lint() {
+            warn!("{}", lint);
+        }
+        return;
+    }
+    let policy = if args.iter().any(|arg| arg == "--first-wins") {
+        OverlapPolicy::FirstWins
+    } else {
+        OverlapPolicy::Reject
+    };
+
+    let part_one = PartOne::new(string).with_policy(policy).run().unwrap();
     info!("Part One: {}", part_one);
 
-    let part_two = PartTwo::new(string).run();
+    let part_two = PartTwo::new(string).with_policy(policy).run().unwrap();
     info!("Part Two: {}", part_two);
 }
@@ -1,45 +1,23 @@
-use std::str::FromStr;
+/*
+--- Part Two ---
+Everyone will starve if you only plant such a small number of seeds. Re-reading the almanac, it looks like the seeds: line actually describes ranges of seed numbers.
 
-use itertools::Itertools;
-use log::debug;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+The values on the initial seeds: line come in pairs. Within each pair, the first value is the start of the range and the second value is the length of the range. So, in the first line of the example above:
 
-struct SeedMap {
-    start: i64,
-    end: i64,
-    offset: i64,
-}
+seeds: 79 14 55 13
+This line describes two ranges of seed numbers to be planted in the garden. The first range starts with seed number 79 and contains 14 values: 79, 80, ..., 91, 92. The second range starts with seed number 55 and contains 13 values: 55, 56, ..., 66, 67.
 
-impl FromStr for SeedMap {
-    type Err = &'static str;
+Now, rather than considering four seed numbers, you need to consider a total of 27 seed numbers.
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = input.trim().splitn(3, ' ').collect();
-
-        if parts.len() != 3 {
-            return Err("Input string must have exactly three parts");
-        }
+In the above example, the lowest location number can be obtained from seed number 82, which corresponds to soil 84, fertilizer 84, water 84, light 77, temperature 45, humidity 46, and location 46. So, the lowest location number is 46.
 
-        let destination: i64 = parts[0].parse().map_err(|_| "Invalid destination")?;
-        let source: i64 = parts[1].parse().map_err(|_| "Invalid source")?;
-        let range: i64 = parts[2].parse().map_err(|_| "Invalid range")?;
+Consider all of the initial seed numbers listed in the ranges on the first line of the almanac. What is the lowest location number that corresponds to any of the initial seed numbers?
+*/
+use itertools::Itertools;
+use tracing::{debug, info_span};
 
-        Ok(SeedMap {
-            start: source,
-            end: source + (range - 1),
-            offset: destination - source,
-        })
-    }
-}
+use crate::almanac::{Almanac, OverlapPolicy};
 
-impl SeedMap {
-    fn transform(&self, input: i64) -> i64 {
-        if input >= self.start && input <= self.end {
-            return input + self.offset;
-        }
-        input
-    }
-}
 #[derive(Copy, Clone, Debug)]
 struct Seed {
     start: i64,
@@ -49,104 +27,84 @@ struct Seed {
 #[derive(Debug)]
 pub struct PartTwo {
     input: String,
+    policy: OverlapPolicy,
 }
 
 impl PartTwo {
     pub fn new(input: &str) -> Self {
         Self {
             input: input.to_string(),
+            policy: OverlapPolicy::default(),
         }
     }
 
-    pub fn run(&self) -> i64 {
-        Self::part2(&self.input)
+    pub fn with_policy(mut self, policy: OverlapPolicy) -> Self {
+        self.policy = policy;
+        self
     }
 
-    fn prune_seeds(seeds: &[Seed]) -> Vec<Seed> {
-        let mut output: Vec<Seed> = Vec::new();
-        let mut end = -1;
-        for seed in seeds {
-            if seed.start + seed.range - 1 <= end {
-                continue;
-            }
-            if seed.start > end {
-                output.push(Seed {
-                    start: seed.start,
-                    range: seed.range,
-                });
-                end = seed.start + seed.range - 1;
-                continue;
-            }
-            output.push(Seed {
-                start: end + 1,
-                range: seed.start + seed.range - end,
-            });
-            end = seed.start + seed.range - 1;
-        }
-        output
+    #[tracing::instrument(skip(self))]
+    pub fn run(&self) -> Result<i64, String> {
+        Self::part2(&self.input, self.policy)
     }
 
-    fn part2(input: &str) -> i64 {
-        let mut inputs = input.trim().split("\n\n");
-
-        // Processing seeds
-        let seeds: Vec<Seed> = {
-            let seed_input = inputs.next().unwrap().trim().split_once(": ").unwrap().1;
-            seed_input
-                .split_whitespace()
-                .tuples()
-                .map(|(start, range)| Seed {
-                    start: start.parse().unwrap(),
-                    range: range.parse().unwrap(),
-                })
-                .sorted_by_key(|seed| seed.start)
-                .collect()
-        };
-        let pruned_seeds = Self::prune_seeds(&seeds);
-
-        // Processing transformers
-        let transformers: Result<Vec<Vec<SeedMap>>, &'static str> = inputs
-            .map(|x| x.trim().lines().skip(1).map(SeedMap::from_str).collect())
+    fn part2(input: &str, policy: OverlapPolicy) -> Result<i64, String> {
+        let almanac = input.parse::<Almanac>()?.with_policy(policy);
+        let seeds: Vec<Seed> = almanac
+            .seeds
+            .iter()
+            .tuples()
+            .map(|(&start, &range)| Seed { start, range })
             .collect();
-        let transformers = transformers.unwrap();
 
-        pruned_seeds
-            .par_iter()
+        // Seed to location is a single piecewise-linear function, so the
+        // lowest location of a seed range is at the start of one of its pieces.
+        let function = info_span!("compose").in_scope(|| almanac.function("seed", "location"))?;
+
+        seeds
+            .iter()
             .map(|x| {
-                debug!("Processing seed: {:?}", x);
-                let result = (x.start..x.start + x.range)
-                    .map(|i| {
-                        transformers.iter().fold(i, |output, maps| {
-                            maps.iter()
-                                .find_map(|map| {
-                                    let result = map.transform(output);
-                                    if result == output {
-                                        None
-                                    } else {
-                                        Some(result)
-                                    }
-                                })
-                                .unwrap_or(output)
-                        })
-                    })
-                    .min()
-                    .unwrap_or(i64::MAX);
-                debug!("Finished parsing seed {:?} with result {}", x, result);
-                result
+                let end = x
+                    .start
+                    .checked_add(x.range)
+                    .ok_or_else(|| format!("Seed range {:?} overflows i64", x))?;
+                let result = function.min_over(x.start..end);
+                debug!("Finished parsing seed {:?} with result {:?}", x, result);
+                Ok(result)
             })
+            .collect::<Result<Vec<_>, String>>()?
+            .into_iter()
+            .flatten()
             .min()
-            .unwrap()
+            .ok_or_else(|| "No seeds".to_string())
     }
 }
 
 #[cfg(test)]
 mod tests_1 {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::almanac::tests::{locate, maps};
 
     #[test]
     fn part_2() {
         let input = include_str!("../test_data.txt");
-        let output = PartTwo::new(input).run();
+        let output = PartTwo::new(input).run().unwrap();
         assert_eq!(output, 46);
     }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(seeds in prop::collection::vec((0..250i64, 1..20i64), 1..4), maps in maps()) {
+            let input = format!("seeds: {}\n\n{}", seeds.iter().map(|(start, range)| format!("{} {}", start, range)).collect::<Vec<_>>().join(" "), maps);
+            let expected = seeds
+                .iter()
+                .flat_map(|&(start, range)| start..start + range)
+                .map(|seed| locate(&maps, seed))
+                .min()
+                .unwrap();
+            prop_assert_eq!(PartTwo::new(&input).run(), Ok(expected));
+        }
+    }
 }
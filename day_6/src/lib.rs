@@ -1,2 +1,3 @@
 pub mod part_one;
 pub mod part_two;
+pub mod race;
@@ -1,11 +1,13 @@
 use day_6::part_one::PartOne;
 use day_6::part_two::PartTwo;
 use dotenv::dotenv;
-use log::info;
+use tracing::info;
 
 fn setup() {
     dotenv().ok();
-    env_logger::init();
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
 }
 
 fn main() {
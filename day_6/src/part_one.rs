@@ -41,35 +41,9 @@ To see how much margin of error you have, determine the number of ways you can b
 
 Determine the number of ways you could beat the record in each race. What do you get if you multiply these numbers together?
 */
-use rayon::iter::{
-    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
-};
+use num_bigint::BigUint;
 
-#[derive(Debug)]
-pub struct Race {
-    total_time: i32,
-    record_distance: i32,
-}
-
-impl Race {
-    fn new(total_time: i32, record_distance: i32) -> Self {
-        Self {
-            total_time,
-            record_distance,
-        }
-    }
-
-    fn calculate_ways_to_win(&self) -> i32 {
-        let mut ways_to_win = 0;
-        for x in 0..self.total_time {
-            let distance = x * (self.total_time - x);
-            if distance > self.record_distance {
-                ways_to_win += 1;
-            }
-        }
-        ways_to_win
-    }
-}
+use crate::race::{parse_races, Kerning, Race};
 
 #[derive(Debug)]
 pub struct PartOne {
@@ -83,47 +57,60 @@ impl PartOne {
         }
     }
 
-    pub fn run(&self) -> i64 {
-        let races = self.parse_input();
-        let ways_to_win_per_race: Vec<i32> = races
-            .par_iter()
-            .map(|race| race.calculate_ways_to_win())
-            .collect();
-        ways_to_win_per_race.iter().map(|&x| x as i64).product()
-    }
-
-    fn parse_input(&self) -> Vec<Race> {
-        let mut lines = self.input.lines();
-
-        let times_line = lines.next().unwrap_or("");
-        let distances_line = lines.next().unwrap_or("");
-
-        let times: Vec<i32> = times_line
-            .split_whitespace()
-            .filter_map(|word| word.parse().ok())
-            .collect();
-
-        let distances: Vec<i32> = distances_line
-            .split_whitespace()
-            .filter_map(|word| word.parse().ok())
-            .collect();
-
-        times
-            .into_par_iter()
-            .zip(distances.into_par_iter())
-            .map(|(total_time, record_distance)| Race::new(total_time, record_distance))
-            .collect()
+    #[tracing::instrument(skip(self))]
+    pub fn run(&self) -> BigUint {
+        let races = parse_races(&self.input, Kerning::Separate).unwrap();
+        races.iter().map(Race::ways_to_win).product()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn part_1() {
         let input = include_str!("../test_data.txt");
         let output = PartOne::new(input).run();
-        assert_eq!(output, 288);
+        assert_eq!(output, BigUint::from(288u32));
+    }
+
+    /// Up to three races short enough to try every hold, even once the
+    /// columns are joined.
+    fn races() -> impl Strategy<Value = Vec<(u64, u64)>> {
+        prop::collection::vec((0..60u64, 0..1000u64), 1..=3)
+    }
+
+    fn input(races: &[(u64, u64)]) -> String {
+        let line = |label: &str, column: fn(&(u64, u64)) -> u64| {
+            races.iter().fold(label.to_string(), |line, race| {
+                format!("{} {:>4}", line, column(race))
+            })
+        };
+        format!(
+            "{}\n{}",
+            line("Time:", |race| race.0),
+            line("Distance:", |race| race.1)
+        )
+    }
+
+    /// Multiplies the number of holds beating the record of each race, trying
+    /// every hold.
+    fn reference(races: &[(u64, u64)]) -> u64 {
+        races
+            .iter()
+            .map(|&(time, distance)| {
+                (0..=time).filter(|x| x * (time - x) > distance).count() as u64
+            })
+            .product()
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(races in races()) {
+            prop_assert_eq!(PartOne::new(&input(&races)).run(), BigUint::from(reference(&races)));
+        }
     }
 }
@@ -1,4 +1,3 @@
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 /*
 --- Part Two ---
 As the race is about to start, you realize the piece of paper with race times and record distances you got earlier actually just has very bad kerning. There's really only one race - ignore the spaces between the numbers on each line.
@@ -15,31 +14,9 @@ Now, you have to figure out how many ways there are to win this single race. In
 
 How many ways can you beat the record in this one much longer race?
 */
-#[derive(Debug)]
-pub struct Race {
-    total_time: i64,
-    record_distance: i64,
-}
-
-impl Race {
-    fn new(total_time: i64, record_distance: i64) -> Self {
-        Self {
-            total_time,
-            record_distance,
-        }
-    }
+use num_bigint::BigUint;
 
-    fn calculate_ways_to_win(&self) -> i64 {
-        let mut ways_to_win = 0;
-        for x in 0..self.total_time {
-            let distance = x * (self.total_time - x);
-            if distance > self.record_distance {
-                ways_to_win += 1;
-            }
-        }
-        ways_to_win
-    }
-}
+use crate::race::{parse_races, Kerning, Race};
 
 #[derive(Debug)]
 pub struct PartTwo {
@@ -53,46 +30,62 @@ impl PartTwo {
         }
     }
 
-    pub fn run(&self) -> i64 {
-        let races = self.parse_input();
-        let ways_to_win_per_race: Vec<i64> = races
-            .par_iter()
-            .map(|race| race.calculate_ways_to_win())
-            .collect();
-        ways_to_win_per_race.par_iter().map(|&x| x as i64).product()
-    }
-
-    fn parse_input(&self) -> Vec<Race> {
-        let mut lines = self.input.lines();
-
-        let time_line = lines
-            .next()
-            .unwrap_or("")
-            .split_whitespace()
-            .skip(1)
-            .collect::<String>();
-        let distance_line = lines
-            .next()
-            .unwrap_or("")
-            .split_whitespace()
-            .skip(1)
-            .collect::<String>();
-
-        let total_time = time_line.parse::<i64>().unwrap_or(0);
-        let record_distance = distance_line.parse::<i64>().unwrap_or(0);
-
-        vec![Race::new(total_time, record_distance)]
+    #[tracing::instrument(skip(self))]
+    pub fn run(&self) -> BigUint {
+        let races = parse_races(&self.input, Kerning::Single).unwrap();
+        races.iter().map(Race::ways_to_win).product()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn part_2() {
         let input = include_str!("../test_data.txt");
         let output = PartTwo::new(input).run();
-        assert_eq!(output, 288);
+        assert_eq!(output, BigUint::from(71503u32));
+    }
+
+    /// Up to three races short enough to try every hold, even once the
+    /// columns are joined.
+    fn races() -> impl Strategy<Value = Vec<(u64, u64)>> {
+        prop::collection::vec((0..60u64, 0..1000u64), 1..=3)
+    }
+
+    fn input(races: &[(u64, u64)]) -> String {
+        let line = |label: &str, column: fn(&(u64, u64)) -> u64| {
+            races.iter().fold(label.to_string(), |line, race| {
+                format!("{} {:>4}", line, column(race))
+            })
+        };
+        format!(
+            "{}\n{}",
+            line("Time:", |race| race.0),
+            line("Distance:", |race| race.1)
+        )
+    }
+
+    /// Joins the columns into a single race and tries every hold.
+    fn reference(races: &[(u64, u64)]) -> u64 {
+        let join = |column: fn(&(u64, u64)) -> u64| {
+            races
+                .iter()
+                .map(|race| column(race).to_string())
+                .collect::<String>()
+        };
+        let time: u64 = join(|race| race.0).parse().unwrap();
+        let distance: u64 = join(|race| race.1).parse().unwrap();
+        (0..=time).filter(|x| x * (time - x) > distance).count() as u64
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(races in races()) {
+            prop_assert_eq!(PartTwo::new(&input(&races)).run(), BigUint::from(reference(&races)));
+        }
     }
 }
@@ -0,0 +1,193 @@
+use num_bigint::BigUint;
+
+/// How the columns of the race sheet are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kerning {
+    /// Every column is its own race.
+    Separate,
+    /// The spaces are bad kerning: all digits of a line form one number.
+    Single,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Race {
+    pub total_time: BigUint,
+    pub record_distance: BigUint,
+}
+
+impl Race {
+    pub fn new(total_time: impl Into<BigUint>, record_distance: impl Into<BigUint>) -> Self {
+        Self {
+            total_time: total_time.into(),
+            record_distance: record_distance.into(),
+        }
+    }
+
+    fn distance(&self, hold: &BigUint) -> BigUint {
+        if hold > &self.total_time {
+            return BigUint::default();
+        }
+        hold * (&self.total_time - hold)
+    }
+
+    /// Holding the button for `x` beats the record when `x * (t - x) > d`.
+    /// The winning holds are the integers strictly between the roots of
+    /// `x² - tx + d`, found with an integer square root and nudged to the
+    /// exact boundary.
+    pub fn ways_to_win(&self) -> BigUint {
+        let t = &self.total_time;
+        let d = &self.record_distance;
+        let discriminant = t * t;
+        let four_d = d * 4u32;
+        if discriminant <= four_d {
+            return BigUint::default();
+        }
+        let root = (discriminant - four_d).sqrt();
+
+        let mut low = (t - &root) / 2u32;
+        while low > BigUint::default() && self.distance(&(&low - 1u32)) > *d {
+            low -= 1u32;
+        }
+        while self.distance(&low) <= *d {
+            low += 1u32;
+            if &low * 2u32 > *t {
+                return BigUint::default();
+            }
+        }
+        // The distance is symmetric around t / 2, so the last winning hold
+        // is `t - low`.
+        t - &low * 2u32 + 1u32
+    }
+}
+
+fn parse_line(line: Option<&str>, label: &str, kerning: Kerning) -> Result<Vec<BigUint>, String> {
+    let values = line
+        .and_then(|line| line.trim().strip_prefix(label))
+        .ok_or_else(|| format!("Expected a line starting with {:?}", label))?;
+    let columns: Vec<&str> = values.split_whitespace().collect();
+    let parse = |value: &str| {
+        value
+            .parse::<BigUint>()
+            .map_err(|e| format!("{} {:?}: {}", label, value, e))
+    };
+    match kerning {
+        Kerning::Separate => columns.into_iter().map(parse).collect(),
+        Kerning::Single => Ok(vec![parse(&columns.concat())?]),
+    }
+}
+
+/// Reads the `Time:` and `Distance:` lines of a race sheet.
+#[tracing::instrument(name = "parse", skip_all)]
+pub fn parse_races(input: &str, kerning: Kerning) -> Result<Vec<Race>, String> {
+    let mut lines = input.lines().filter(|line| !line.trim().is_empty());
+    let times = parse_line(lines.next(), "Time:", kerning)?;
+    let distances = parse_line(lines.next(), "Distance:", kerning)?;
+    if let Some(line) = lines.next() {
+        return Err(format!("Unexpected line {:?}", line));
+    }
+    if times.len() != distances.len() {
+        return Err(format!(
+            "{} times but {} distances",
+            times.len(),
+            distances.len()
+        ));
+    }
+    Ok(times
+        .into_iter()
+        .zip(distances)
+        .map(|(total_time, record_distance)| Race {
+            total_time,
+            record_distance,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn brute_force(total_time: u64, record_distance: u64) -> u64 {
+        (0..=total_time)
+            .filter(|x| x * (total_time - x) > record_distance)
+            .count() as u64
+    }
+
+    #[test]
+    fn test_ways_to_win() {
+        for total_time in 0..60u64 {
+            for record_distance in 0..(total_time * total_time / 4 + 3) {
+                let race = Race::new(total_time, record_distance);
+                assert_eq!(
+                    race.ways_to_win(),
+                    BigUint::from(brute_force(total_time, record_distance)),
+                    "time {} distance {}",
+                    total_time,
+                    record_distance
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_races() {
+        let input = include_str!("../test_data.txt");
+        let races = parse_races(input, Kerning::Separate).unwrap();
+        assert_eq!(
+            races,
+            vec![
+                Race::new(7u32, 9u32),
+                Race::new(15u32, 40u32),
+                Race::new(30u32, 200u32)
+            ]
+        );
+        let races = parse_races(input, Kerning::Single).unwrap();
+        assert_eq!(races, vec![Race::new(71530u32, 940200u32)]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_races("Time: 7 15\nDistance: 9", Kerning::Separate),
+            Err("2 times but 1 distances".to_string())
+        );
+        assert!(parse_races("Time: 7 x\nDistance: 9 4", Kerning::Separate).is_err());
+        assert!(parse_races("Time: 7 -1\nDistance: 9 4", Kerning::Single).is_err());
+        assert!(parse_races("Time: 7\n", Kerning::Separate).is_err());
+        assert!(parse_races("Distance: 9\nTime: 7", Kerning::Separate).is_err());
+        assert!(parse_races("Time: 7\nDistance: 9\nTime: 7", Kerning::Separate).is_err());
+    }
+
+    #[test]
+    fn test_kerned_number_beyond_64_bits() {
+        let input = "Time: 4000000000 0000000000\nDistance: 1 0000000000 0000000000";
+        let races = parse_races(input, Kerning::Single).unwrap();
+        let race = &races[0];
+        assert!(race.total_time > BigUint::from(u64::MAX));
+
+        // 4e19 * 4e19 / 4 is far above the record, so nearly every hold wins.
+        let t: BigUint = "40000000000000000000".parse().unwrap();
+        let d: BigUint = "100000000000000000000".parse().unwrap();
+        let ways = race.ways_to_win();
+        assert!(ways > BigUint::from(u64::MAX));
+        assert!(ways < t);
+        let low = (&t + 1u32 - &ways) / 2u32;
+        assert!(&low * (&t - &low) > d);
+        assert!((&low - 1u32) * (&t - &low + 1u32) <= d);
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = parse_races(&input, Kerning::Separate);
+            let _ = parse_races(&input, Kerning::Single);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "(Time|Distance):( +-?[0-9]{1,30}){0,4}\n(Time|Distance):( +-?[0-9]{1,30}){0,4}\n?") {
+            let _ = parse_races(&input, Kerning::Separate);
+            let _ = parse_races(&input, Kerning::Single);
+        }
+    }
+}
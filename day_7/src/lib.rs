@@ -1,3 +1,3 @@
-#![feature(slice_group_by)]
 pub mod part_one;
 pub mod part_two;
+pub mod ranking;
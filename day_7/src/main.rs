@@ -1,7 +1,10 @@
-use day_6::part_one::PartOne;
-use day_6::part_two::PartTwo;
+use std::env;
+
+use day_7::part_one::PartOne;
+use day_7::part_two::PartTwo;
+use day_7::ranking::RankMode;
 use dotenv::dotenv;
-use tracing::info;
+use tracing::{info, warn};
 
 fn setup() {
     dotenv().ok();
@@ -15,9 +18,43 @@ fn main() {
 
     let string = include_str!("../input.txt");
 
-    let part_one = PartOne::new(string).run();
-    info!("Part One: {}", part_one);
+    // `--leaderboard [strict|dense]` prints every hand instead of the totals,
+    // `--jokers` what the jokers of part two were played as.
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("--leaderboard") => {
+            let mode = args
+                .next()
+                .map_or(Ok(RankMode::Strict), |mode| mode.parse::<RankMode>())
+                .unwrap();
+            println!(
+                "Part One\n{}",
+                PartOne::new(string).unwrap().leaderboard(mode)
+            );
+            println!(
+                "Part Two\n{}",
+                PartTwo::new(string).unwrap().leaderboard(mode)
+            );
+            return;
+        }
+        Some("--jokers") => {
+            for (hand, substitution) in PartTwo::new(string).unwrap().substitutions() {
+                println!("{} -> {}", hand, substitution);
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let part_one = PartOne::new(string).unwrap().leaderboard(RankMode::Strict);
+    for tie in &part_one.ties {
+        warn!("Part One: {} is dealt {} times", tie.hand, tie.ranks.len());
+    }
+    info!("Part One: {}", part_one.total_winnings());
 
-    let part_two = PartTwo::new(string).run();
-    info!("Part Two: {}", part_two);
+    let part_two = PartTwo::new(string).unwrap().leaderboard(RankMode::Strict);
+    for tie in &part_two.ties {
+        warn!("Part Two: {} is dealt {} times", tie.hand, tie.ranks.len());
+    }
+    info!("Part Two: {}", part_two.total_winnings());
 }
@@ -51,32 +51,24 @@ Now, you can determine the total winnings of this set of hands by adding up the
 
 Find the rank of every hand in your set. What are the total winnings?
 */
-use std::{ops::Deref, str::FromStr};
+use std::str::FromStr;
 
 use itertools::Itertools;
-use rayon::iter::{ParallelBridge, ParallelIterator};
-
-#[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
-enum HandType {
-    FiveOfAKind = 6,
-    FourOfAKind = 5,
-    FullHouse = 4,
-    ThreeOfAKind = 3,
-    TwoPair = 2,
-    OnePair = 1,
-    HighCard = 0,
-}
+
+use crate::ranking::{Contender, HandType, Leaderboard, RankMode};
 
 #[derive(Debug)]
 struct Hand {
+    label: String,
     bid: u32,
     cards: (u32, u32, u32, u32, u32),
     hand_type: HandType,
 }
 
 impl Hand {
-    fn new(bid: u32, cards: (u32, u32, u32, u32, u32), hand_type: HandType) -> Self {
+    fn new(label: &str, bid: u32, cards: (u32, u32, u32, u32, u32), hand_type: HandType) -> Self {
         Self {
+            label: label.to_string(),
             hand_type,
             cards,
             bid,
@@ -85,38 +77,29 @@ impl Hand {
 }
 
 impl FromStr for Hand {
-    type Err = ();
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (hand, bid) = s.split_once(" ").unwrap();
-        let bid = bid.parse::<u32>().unwrap();
-
-        let counts = hand.chars().counts();
-        let values = counts.values().sorted().join("");
-        let hand_type = match values.deref() {
-            "5" => HandType::FiveOfAKind,
-            "14" => HandType::FourOfAKind,
-            "23" => HandType::FullHouse,
-            "113" => HandType::ThreeOfAKind,
-            "122" => HandType::TwoPair,
-            "1112" => HandType::OnePair,
-            "11111" => HandType::HighCard,
-            value => unreachable!("should never happen. Encountered `{}`", value),
-        };
-        let cards =
-            hand.chars()
-                .map(|card| match card {
-                    'A' => 14,
-                    'K' => 13,
-                    'Q' => 12,
-                    'J' => 11,
-                    'T' => 10,
-                    value => value.to_digit(10).unwrap(),
-                })
-                .collect_tuple()
-                .unwrap();
-
-        Ok(Self::new(bid, cards, hand_type))
+        let (hand, bid) = s.split_once(' ').ok_or_else(|| format!("No bid: {}", s))?;
+        let bid = bid
+            .parse::<u32>()
+            .map_err(|e| format!("Invalid bid {:?}: {}", bid, e))?;
+        let cards = hand
+            .chars()
+            .map(|card| match card {
+                'A' => Some(14),
+                'K' => Some(13),
+                'Q' => Some(12),
+                'J' => Some(11),
+                'T' => Some(10),
+                '2'..='9' => card.to_digit(10),
+                _ => None,
+            })
+            .collect::<Option<Vec<u32>>>()
+            .and_then(|cards| cards.into_iter().collect_tuple())
+            .ok_or_else(|| format!("Invalid hand {:?}", hand))?;
+        let hand_type = HandType::of(hand);
+        Ok(Self::new(hand, bid, cards, hand_type))
     }
 }
 
@@ -126,41 +109,120 @@ pub struct PartOne {
 }
 
 impl PartOne {
-    pub fn new(input: &str) -> Self {
-        let hands = input
-            .lines()
-            .par_bridge()
-            .map(|line| line.parse::<Hand>().unwrap())
-            .collect();
-        Self { hands }
+    /// Reads one hand and its bid per line.
+    #[tracing::instrument(name = "parse", skip_all)]
+    pub fn new(input: &str) -> Result<Self, String> {
+        let hands = input.lines().map(str::parse).collect::<Result<_, _>>()?;
+        Ok(Self { hands })
+    }
+
+    /// Every hand from weakest to strongest, with what it wins.
+    pub fn leaderboard(&self, mode: RankMode) -> Leaderboard {
+        Leaderboard::new(
+            self.hands.iter().map(|hand| Contender {
+                label: &hand.label,
+                hand_type: hand.hand_type,
+                cards: hand.cards,
+                bid: hand.bid,
+            }),
+            mode,
+        )
     }
 
-    #[tracing::instrument(skip(self))]
     pub fn run(&self) -> u32 {
-        let winnings = self
-            .hands
-            .iter()
-            .sorted_by(|a, b| {
-                a.hand_type
-                    .cmp(&b.hand_type)
-                    .then_with(|| a.cards.cmp(&b.cards))
-            })
-            .enumerate()
-            .par_bridge()
-            .map(|(index, hand)| (index as u32 + 1) * hand.bid)
-            .sum();
-        winnings
+        self.leaderboard(RankMode::Strict).total_winnings()
     }
 }
 
+#[tracing::instrument(skip(input))]
+pub fn run(input: &str) -> Result<u32, String> {
+    Ok(PartOne::new(input)?.run())
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn part_1() {
         let input = include_str!("../test_data.txt");
-        let output = PartOne::new(input).run();
+        let output = PartOne::new(input).unwrap().run();
         assert_eq!(output, 6440);
     }
+
+    #[test]
+    fn test_leaderboard() {
+        let input = include_str!("../test_data.txt");
+        let leaderboard = PartOne::new(input).unwrap().leaderboard(RankMode::Strict);
+        let order: Vec<&str> = leaderboard
+            .standings
+            .iter()
+            .map(|standing| standing.hand.as_str())
+            .collect();
+        assert_eq!(order, ["32T3K", "KTJJT", "KK677", "T55J5", "QQQJA"]);
+        assert!(leaderboard.ties.is_empty());
+    }
+
+    #[test]
+    fn invalid_hands() {
+        assert!(PartOne::new("32T3K 765\nT55J5 684\n").is_ok());
+        assert!(PartOne::new("32T3K").is_err());
+        assert!(PartOne::new("32T3K x").is_err());
+        assert!(PartOne::new("32T3 765").is_err());
+        assert!(PartOne::new("32T3K1 765").is_err());
+        assert!(PartOne::new("32T1K 765").is_err());
+    }
+
+    /// Names the type from the largest and second largest count of a label,
+    /// and compares the cards by their position in the label list.
+    fn reference(input: &str) -> u32 {
+        let labels = "23456789TJQKA";
+        let mut hands: Vec<(usize, usize, Vec<usize>, u32)> = input
+            .lines()
+            .map(|line| {
+                let (hand, bid) = line.split_once(' ').unwrap();
+                let mut counts: Vec<usize> = labels
+                    .chars()
+                    .map(|label| hand.matches(label).count())
+                    .collect();
+                counts.sort_unstable_by(|a, b| b.cmp(a));
+                let cards = hand
+                    .chars()
+                    .map(|card| labels.find(card).unwrap())
+                    .collect();
+                (counts[0], counts[1], cards, bid.parse().unwrap())
+            })
+            .collect();
+        hands.sort_by(|a, b| (a.0, a.1, &a.2).cmp(&(b.0, b.1, &b.2)));
+        hands
+            .iter()
+            .enumerate()
+            .map(|(rank, (_, _, _, bid))| (rank as u32 + 1) * bid)
+            .sum()
+    }
+
+    fn hands() -> impl Strategy<Value = String> {
+        let hand =
+            ("[2-9TJQKA]{5}", 1..1000u32).prop_map(|(hand, bid)| format!("{} {}", hand, bid));
+        prop::collection::vec(hand, 1..64).prop_map(|hands| hands.join("\n"))
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = PartOne::new(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "[2-9TJQKA0 \n]{0,32}") {
+            let _ = PartOne::new(&input);
+        }
+
+        #[test]
+        fn agrees_with_reference(input in hands()) {
+            prop_assert_eq!(PartOne::new(&input).unwrap().run(), reference(&input));
+        }
+    }
 }
@@ -20,32 +20,82 @@ With the new joker rule, the total winnings in this example are 5905.
 
 Using the new joker rule, find the rank of every hand in your set. What are the new total winnings?
 */
-use std::{ops::Deref, str::FromStr};
-
-use itertools::{Itertools, Position};
-use rayon::iter::{ParallelBridge, ParallelIterator};
-
-#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
-enum HandType {
-    FiveOfAKind = 6,
-    FourOfAKind = 5,
-    FullHouse = 4,
-    ThreeOfAKind = 3,
-    TwoPair = 2,
-    OnePair = 1,
-    HighCard = 0,
-}
+use std::str::FromStr;
+
+use itertools::Itertools;
 
-#[derive(Clone, Copy, Debug)]
+use crate::ranking::{Contender, HandType, Leaderboard, RankMode};
+
+#[derive(Clone, Debug)]
 struct Hand {
+    label: String,
     bid: u32,
     cards: (u32, u32, u32, u32, u32),
     hand_type: HandType,
+    substitution: String,
+}
+
+/// Every label but the joker, strongest first.
+const LABELS: [char; 12] = ['A', 'K', 'Q', 'T', '9', '8', '7', '6', '5', '4', '3', '2'];
+
+fn strength(card: char) -> u32 {
+    match card {
+        'A' => 14,
+        'K' => 13,
+        'Q' => 12,
+        'J' => 1,
+        'T' => 10,
+        value => value.to_digit(10).unwrap(),
+    }
+}
+
+/// The strongest type `hand` can make with its jokers, together with the
+/// concrete hand the jokers turn it into, e.g. `KTJJT` becomes `KTTTT`.
+///
+/// Adding every joker to the most common other label is always optimal: it
+/// raises the largest count, and the type only depends on the counts. Ties
+/// between labels go to the stronger one so the substitution is stable.
+pub fn classify(hand: &str) -> (HandType, String) {
+    let target = hand
+        .chars()
+        .filter(|&card| card != 'J')
+        .counts()
+        .into_iter()
+        .max_by_key(|&(card, count)| (count, strength(card)))
+        .map_or('A', |(card, _)| card);
+    let substitution = hand.replace('J', &target.to_string());
+    (HandType::of(&substitution), substitution)
+}
+
+/// The strongest type `hand` can make, found by trying every label for every
+/// joker. Exponential in the number of jokers, only meant to check
+/// [`classify`].
+pub fn brute_force(hand: &str) -> HandType {
+    match hand.find('J') {
+        None => HandType::of(hand),
+        Some(index) => LABELS
+            .iter()
+            .map(|&label| {
+                let mut replaced = hand.to_string();
+                replaced.replace_range(index..index + 1, &label.to_string());
+                brute_force(&replaced)
+            })
+            .max()
+            .unwrap(),
+    }
 }
 
 impl Hand {
-    fn new(bid: u32, cards: (u32, u32, u32, u32, u32), hand_type: HandType) -> Self {
+    fn new(
+        label: &str,
+        bid: u32,
+        cards: (u32, u32, u32, u32, u32),
+        hand_type: HandType,
+        substitution: String,
+    ) -> Self {
         Self {
+            label: label.to_string(),
+            substitution,
             hand_type,
             cards,
             bid,
@@ -54,56 +104,28 @@ impl Hand {
 }
 
 impl FromStr for Hand {
-    type Err = ();
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (hand, bid) = s.split_once(" ").unwrap();
-        let bid = bid.parse::<u32>().unwrap();
-
-        let counts = hand.chars().counts();
-        let values = if let Some(joker_count) = counts.get(&'J') {
-            if *joker_count == 5 {
-                "5".to_string()
-            } else {
-                counts
-                    .iter()
-                    .filter_map(|(key, value)| (key != &'J').then_some(value))
-                    .sorted()
-                    .with_position()
-                    .map(|(position, value)| match position {
-                        Position::Last | Position::Only => value + joker_count,
-                        _ => *value,
-                    })
-                    .join("")
-            }
-        } else {
-            counts.values().sorted().join("")
-        };
-
-        let hand_type = match values.deref() {
-            "5" => HandType::FiveOfAKind,
-            "14" => HandType::FourOfAKind,
-            "23" => HandType::FullHouse,
-            "113" => HandType::ThreeOfAKind,
-            "122" => HandType::TwoPair,
-            "1112" => HandType::OnePair,
-            "11111" => HandType::HighCard,
-            _ => unreachable!(),
-        };
-        let cards =
-            hand.chars()
-                .map(|card| match card {
-                    'A' => 14,
-                    'K' => 13,
-                    'Q' => 12,
-                    'J' => 1,
-                    'T' => 10,
-                    value => value.to_digit(10).unwrap(),
-                })
-                .collect_tuple()
-                .unwrap();
-
-        Ok(Self::new(bid, cards, hand_type))
+        let (hand, bid) = s.split_once(' ').ok_or_else(|| format!("No bid: {}", s))?;
+        let bid = bid
+            .parse::<u32>()
+            .map_err(|e| format!("Invalid bid {:?}: {}", bid, e))?;
+        if !hand
+            .chars()
+            .all(|card| card == 'J' || LABELS.contains(&card))
+        {
+            return Err(format!("Invalid hand {:?}", hand));
+        }
+        let cards = hand
+            .chars()
+            .map(strength)
+            .collect_tuple()
+            .ok_or_else(|| format!("Invalid hand {:?}", hand))?;
+
+        let (hand_type, substitution) = classify(hand);
+
+        Ok(Self::new(hand, bid, cards, hand_type, substitution))
     }
 }
 
@@ -113,37 +135,168 @@ pub struct PartTwo {
 }
 
 impl PartTwo {
-    pub fn new(input: &str) -> Self {
-        let hands = input
-            .lines()
-            .par_bridge()
-            .map(|line| line.parse::<Hand>().unwrap())
-            .collect();
-        Self { hands }
+    /// Reads one hand and its bid per line.
+    #[tracing::instrument(name = "parse", skip_all)]
+    pub fn new(input: &str) -> Result<Self, String> {
+        let hands = input.lines().map(str::parse).collect::<Result<_, _>>()?;
+        Ok(Self { hands })
     }
 
-    #[tracing::instrument(skip(self))]
-    pub fn run(&self) -> u32 {
-        let winnings = self
-            .hands
+    /// Every hand from weakest to strongest, with what it wins.
+    pub fn leaderboard(&self, mode: RankMode) -> Leaderboard {
+        Leaderboard::new(
+            self.hands.iter().map(|hand| Contender {
+                label: &hand.label,
+                hand_type: hand.hand_type,
+                cards: hand.cards,
+                bid: hand.bid,
+            }),
+            mode,
+        )
+    }
+
+    /// What the jokers became in every hand that holds any, in input order.
+    pub fn substitutions(&self) -> Vec<(&str, &str)> {
+        self.hands
             .iter()
-            .sorted_by_key(|hand| (hand.hand_type, hand.cards))
-            .enumerate()
-            .par_bridge()
-            .map(|(index, hand)| (index as u32 + 1) * hand.bid)
-            .sum();
-        winnings
+            .filter(|hand| hand.label.contains('J'))
+            .map(|hand| (hand.label.as_str(), hand.substitution.as_str()))
+            .collect()
     }
+
+    pub fn run(&self) -> u32 {
+        self.leaderboard(RankMode::Strict).total_winnings()
+    }
+}
+
+#[tracing::instrument(skip(input))]
+pub fn run(input: &str) -> Result<u32, String> {
+    Ok(PartTwo::new(input)?.run())
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
-    fn part_1() {
+    fn part_2() {
         let input = include_str!("../test_data.txt");
-        let output = PartTwo::new(input).run();
+        let output = PartTwo::new(input).unwrap().run();
         assert_eq!(output, 5905);
     }
+
+    #[test]
+    fn test_leaderboard() {
+        let input = include_str!("../test_data.txt");
+        let leaderboard = PartTwo::new(input).unwrap().leaderboard(RankMode::Strict);
+        let order: Vec<&str> = leaderboard
+            .standings
+            .iter()
+            .map(|standing| standing.hand.as_str())
+            .collect();
+        assert_eq!(order, ["32T3K", "KK677", "T55J5", "QQQJA", "KTJJT"]);
+        assert!(leaderboard.ties.is_empty());
+    }
+
+    #[test]
+    fn test_substitutions() {
+        let input = include_str!("../test_data.txt");
+        let part_two = PartTwo::new(input).unwrap();
+        assert_eq!(
+            part_two.substitutions(),
+            [("T55J5", "T5555"), ("KTJJT", "KTTTT"), ("QQQJA", "QQQQA")]
+        );
+        assert_eq!(
+            classify("JJJJJ"),
+            (HandType::FiveOfAKind, "AAAAA".to_string())
+        );
+        assert_eq!(classify("2345J"), (HandType::OnePair, "23455".to_string()));
+    }
+
+    #[test]
+    fn test_classify_is_optimal() {
+        let labels = [
+            'A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2',
+        ];
+        // Types only depend on which labels a hand holds, not their order, so
+        // one hand per multiset covers all 13^5 of them.
+        for hand in labels.into_iter().combinations_with_replacement(5) {
+            let hand: String = hand.into_iter().collect();
+            let (hand_type, substitution) = classify(&hand);
+            assert_eq!(hand_type, brute_force(&hand), "{}", hand);
+            assert_eq!(HandType::of(&substitution), hand_type, "{}", hand);
+            assert!(
+                hand.chars()
+                    .zip(substitution.chars())
+                    .all(|(card, substituted)| card == 'J' || card == substituted),
+                "{} became {}",
+                hand,
+                substitution
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_hands() {
+        assert!(PartTwo::new("32T3K 765\nT55J5 684\n").is_ok());
+        assert!(PartTwo::new("32T3K").is_err());
+        assert!(PartTwo::new("32T3K -1").is_err());
+        assert!(PartTwo::new("JJJJ 765").is_err());
+        assert!(PartTwo::new("32T1K 765").is_err());
+    }
+
+    /// Adds the jokers to the largest count of the other labels, and ranks
+    /// the joker below every other label when comparing cards.
+    fn reference(input: &str) -> u32 {
+        let labels = "J23456789TQKA";
+        let mut hands: Vec<(usize, usize, Vec<usize>, u32)> = input
+            .lines()
+            .map(|line| {
+                let (hand, bid) = line.split_once(' ').unwrap();
+                let mut counts: Vec<usize> = labels[1..]
+                    .chars()
+                    .map(|label| hand.matches(label).count())
+                    .collect();
+                counts.sort_unstable_by(|a, b| b.cmp(a));
+                counts[0] += hand.matches('J').count();
+                let cards = hand
+                    .chars()
+                    .map(|card| labels.find(card).unwrap())
+                    .collect();
+                (counts[0], counts[1], cards, bid.parse().unwrap())
+            })
+            .collect();
+        hands.sort_by(|a, b| (a.0, a.1, &a.2).cmp(&(b.0, b.1, &b.2)));
+        hands
+            .iter()
+            .enumerate()
+            .map(|(rank, (_, _, _, bid))| (rank as u32 + 1) * bid)
+            .sum()
+    }
+
+    /// Jokers are drawn more often than the other labels.
+    fn hands() -> impl Strategy<Value = String> {
+        let hand =
+            ("[2-9TJJJJQKA]{5}", 1..1000u32).prop_map(|(hand, bid)| format!("{} {}", hand, bid));
+        prop::collection::vec(hand, 1..64).prop_map(|hands| hands.join("\n"))
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = PartTwo::new(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "[2-9TJQKA0 \n]{0,32}") {
+            let _ = PartTwo::new(&input);
+        }
+
+        #[test]
+        fn agrees_with_reference(input in hands()) {
+            prop_assert_eq!(PartTwo::new(&input).unwrap().run(), reference(&input));
+        }
+    }
 }
@@ -0,0 +1,259 @@
+use std::{fmt, ops::Deref, str::FromStr};
+
+use itertools::Itertools;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum HandType {
+    FiveOfAKind = 6,
+    FourOfAKind = 5,
+    FullHouse = 4,
+    ThreeOfAKind = 3,
+    TwoPair = 2,
+    OnePair = 1,
+    HighCard = 0,
+}
+
+impl HandType {
+    /// The type of `hand` taken at face value, with no card being wild.
+    pub fn of(hand: &str) -> Self {
+        let counts = hand.chars().counts();
+        let values = counts.values().sorted().join("");
+        match values.deref() {
+            "5" => HandType::FiveOfAKind,
+            "14" => HandType::FourOfAKind,
+            "23" => HandType::FullHouse,
+            "113" => HandType::ThreeOfAKind,
+            "122" => HandType::TwoPair,
+            "1112" => HandType::OnePair,
+            "11111" => HandType::HighCard,
+            value => unreachable!("should never happen. Encountered `{}`", value),
+        }
+    }
+}
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HandType::FiveOfAKind => "Five of a kind",
+            HandType::FourOfAKind => "Four of a kind",
+            HandType::FullHouse => "Full house",
+            HandType::ThreeOfAKind => "Three of a kind",
+            HandType::TwoPair => "Two pair",
+            HandType::OnePair => "One pair",
+            HandType::HighCard => "High card",
+        };
+        f.pad(name)
+    }
+}
+
+/// How hands of equal strength are ranked.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RankMode {
+    /// Every hand gets its own rank, as the puzzle asks. Equal hands keep the
+    /// order they were dealt in.
+    #[default]
+    Strict,
+    /// Equal hands share a rank and the next stronger hand follows directly.
+    Dense,
+}
+
+impl FromStr for RankMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Self::Strict),
+            "dense" => Ok(Self::Dense),
+            _ => Err(format!(
+                "Unknown rank mode {:?}, expected strict or dense",
+                s
+            )),
+        }
+    }
+}
+
+/// A hand as seen by the ranking: its label, what it is worth and its bid.
+#[derive(Clone, Debug)]
+pub(crate) struct Contender<'a> {
+    pub label: &'a str,
+    pub hand_type: HandType,
+    pub cards: (u32, u32, u32, u32, u32),
+    pub bid: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Standing {
+    pub hand: String,
+    pub hand_type: HandType,
+    pub rank: u32,
+    pub bid: u32,
+    pub winnings: u32,
+}
+
+/// Hands that cannot be told apart, with the ranks they ended up on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tie {
+    pub hand: String,
+    pub ranks: Vec<u32>,
+}
+
+/// Every hand from weakest to strongest.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Leaderboard {
+    pub mode: RankMode,
+    pub standings: Vec<Standing>,
+    pub ties: Vec<Tie>,
+}
+
+impl Leaderboard {
+    pub(crate) fn new<'a>(
+        contenders: impl IntoIterator<Item = Contender<'a>>,
+        mode: RankMode,
+    ) -> Self {
+        let mut contenders: Vec<Contender> = contenders.into_iter().collect();
+        // Stable, so that tied hands keep the order they were dealt in.
+        contenders.sort_by_key(|contender| (contender.hand_type, contender.cards));
+
+        let mut standings: Vec<Standing> = Vec::with_capacity(contenders.len());
+        let mut ties: Vec<Tie> = Vec::new();
+        let mut rank = 0;
+        for (index, contender) in contenders.iter().enumerate() {
+            let tied = index > 0 && {
+                let previous = &contenders[index - 1];
+                (previous.hand_type, previous.cards) == (contender.hand_type, contender.cards)
+            };
+            rank = match mode {
+                RankMode::Strict => index as u32 + 1,
+                RankMode::Dense if tied => rank,
+                RankMode::Dense => rank + 1,
+            };
+            if tied {
+                match ties.last_mut() {
+                    Some(tie) if tie.hand == contender.label => tie.ranks.push(rank),
+                    _ => ties.push(Tie {
+                        hand: contender.label.to_string(),
+                        ranks: vec![standings[index - 1].rank, rank],
+                    }),
+                }
+            }
+            standings.push(Standing {
+                hand: contender.label.to_string(),
+                hand_type: contender.hand_type,
+                rank,
+                bid: contender.bid,
+                winnings: rank * contender.bid,
+            });
+        }
+
+        Self {
+            mode,
+            standings,
+            ties,
+        }
+    }
+
+    pub fn total_winnings(&self) -> u32 {
+        self.standings
+            .iter()
+            .map(|standing| standing.winnings)
+            .sum()
+    }
+}
+
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>5}  {:<5}  {:<15}  {:>5}  {:>9}",
+            "rank", "hand", "type", "bid", "winnings"
+        )?;
+        for standing in &self.standings {
+            writeln!(
+                f,
+                "{:>5}  {:<5}  {:<15}  {:>5}  {:>9}",
+                standing.rank, standing.hand, standing.hand_type, standing.bid, standing.winnings
+            )?;
+        }
+        writeln!(f, "total winnings: {}", self.total_winnings())?;
+        for tie in &self.ties {
+            let ranks = tie
+                .ranks
+                .iter()
+                .map(|rank| rank.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(
+                f,
+                "tie: {} dealt {} times, ranks {}",
+                tie.hand,
+                tie.ranks.len(),
+                ranks
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contender(label: &str, cards: (u32, u32, u32, u32, u32), bid: u32) -> Contender<'_> {
+        Contender {
+            label,
+            hand_type: HandType::OnePair,
+            cards,
+            bid,
+        }
+    }
+
+    #[test]
+    fn test_ties() {
+        let contenders = [
+            contender("33456", (3, 3, 4, 5, 6), 10),
+            contender("22456", (2, 2, 4, 5, 6), 1),
+            contender("33456", (3, 3, 4, 5, 6), 20),
+            contender("44567", (4, 4, 5, 6, 7), 100),
+        ];
+
+        let strict = Leaderboard::new(contenders.clone(), RankMode::Strict);
+        let ranks: Vec<(&str, u32, u32)> = strict
+            .standings
+            .iter()
+            .map(|standing| (standing.hand.as_str(), standing.rank, standing.bid))
+            .collect();
+        assert_eq!(
+            ranks,
+            [
+                ("22456", 1, 1),
+                ("33456", 2, 10),
+                ("33456", 3, 20),
+                ("44567", 4, 100)
+            ]
+        );
+        assert_eq!(
+            strict.ties,
+            [Tie {
+                hand: "33456".to_string(),
+                ranks: vec![2, 3]
+            }]
+        );
+        assert_eq!(strict.total_winnings(), 1 + 20 + 60 + 400);
+
+        let dense = Leaderboard::new(contenders, RankMode::Dense);
+        let ranks: Vec<u32> = dense
+            .standings
+            .iter()
+            .map(|standing| standing.rank)
+            .collect();
+        assert_eq!(ranks, [1, 2, 2, 3]);
+        assert_eq!(
+            dense.ties,
+            [Tie {
+                hand: "33456".to_string(),
+                ranks: vec![2, 2]
+            }]
+        );
+        assert_eq!(dense.total_winnings(), 1 + 20 + 40 + 300);
+    }
+}
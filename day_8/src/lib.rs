@@ -1,3 +1,2 @@
-#![feature(slice_group_by)]
 pub mod part_one;
 pub mod part_two;
@@ -33,19 +33,22 @@ Starting at AAA, follow the left/right instructions. How many steps are required
 
 use std::{collections::HashMap, str::FromStr};
 
+/// Where a node leads when going left or right.
 #[derive(Debug)]
-struct Map {
+pub struct Map {
     left: String,
     right: String,
 }
 
 impl FromStr for Map {
-    type Err = nom::Err<nom::error::Error<&'static str>>;
+    type Err = String;
+
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (left, right) = s
-            .trim_matches(|p| p == '(' || p == ')')
-            .split_once(", ")
-            .unwrap_or_else(|| panic!("Failed to parse: {}", s));
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .and_then(|s| s.split_once(", "))
+            .ok_or_else(|| format!("Failed to parse: {}", s))?;
         let left = left.to_string();
         let right = right.to_string();
         Ok(Self { left, right })
@@ -53,33 +56,53 @@ impl FromStr for Map {
 }
 
 #[derive(Debug)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
 
-fn parse_directions(input: &str) -> Vec<Direction> {
-    input
+fn parse_directions(input: &str) -> Result<Vec<Direction>, String> {
+    let line = input
         .lines()
-        .nth(0)
-        .expect("Failed to get first line")
-        .chars()
+        .next()
+        .filter(|line| !line.is_empty())
+        .ok_or("Failed to get first line")?;
+    line.chars()
         .map(|c| match c {
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => panic!("Invalid direction: {}", c),
+            'L' => Ok(Direction::Left),
+            'R' => Ok(Direction::Right),
+            _ => Err(format!("Invalid direction: {}", c)),
         })
         .collect()
 }
 
-fn parse_map(input: &str) -> HashMap<&str, Map> {
+fn parse_map(input: &str) -> Result<HashMap<&str, Map>, String> {
+    let mut lines = input.lines().skip(1);
+    if lines.next().is_some_and(|line| !line.is_empty()) {
+        return Err("Expected a blank line after the directions".to_string());
+    }
     let mut map = HashMap::new();
-    for line in input.lines().skip(2) {
-        let (left, right) = line.split_once(" = ").unwrap();
-        let right = right.parse::<Map>().unwrap();
-        map.insert(left, right);
+    for line in lines {
+        let (left, right) = line
+            .split_once(" = ")
+            .ok_or_else(|| format!("Failed to parse: {}", line))?;
+        map.insert(left, right.parse::<Map>()?);
+    }
+    for next in map.values() {
+        for node in [&next.left, &next.right] {
+            if !map.contains_key(node.as_str()) {
+                return Err(format!("Unknown node: {}", node));
+            }
+        }
     }
-    map
+    Ok(map)
+}
+
+/// Reads the directions on the first line and, after a blank line, one node
+/// per line. Every node a node leads to must be listed too.
+#[tracing::instrument(name = "parse", skip_all)]
+pub fn parse_input(input: &str) -> Result<(Vec<Direction>, HashMap<&str, Map>), String> {
+    Ok((parse_directions(input)?, parse_map(input)?))
 }
 
 fn navigate_to_zzz(node_map: &HashMap<&str, Map>, directions: &[Direction]) -> u32 {
@@ -104,15 +127,16 @@ fn navigate_to_zzz(node_map: &HashMap<&str, Map>, directions: &[Direction]) -> u
     steps
 }
 
-#[tracing::instrument]
+#[tracing::instrument(skip(input))]
 pub fn run(input: &str) -> u32 {
-    let directions = parse_directions(input);
-    let map = parse_map(input);
+    let (directions, map) = parse_input(input).unwrap();
     navigate_to_zzz(&map, &directions)
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -121,4 +145,90 @@ mod tests {
         let output = run(input);
         assert_eq!(output, 6);
     }
+
+    #[test]
+    fn invalid_input() {
+        assert!(parse_input("LR\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)").is_ok());
+        assert!(parse_input("").is_err());
+        assert!(parse_input("LX\n\nAAA = (AAA, AAA)").is_err());
+        assert!(parse_input("LR\nAAA = (AAA, AAA)").is_err());
+        assert!(parse_input("LR\n\nAAA = (AAA, BBB)").is_err());
+        assert!(parse_input("LR\n\nAAA = AAA, AAA").is_err());
+    }
+
+    /// Walks the nodes by their position in the input, giving up once every
+    /// node has been seen at every instruction without reaching `ZZZ`.
+    fn reference(input: &str) -> Option<u32> {
+        let mut lines = input.lines();
+        let directions = lines.next().unwrap().as_bytes();
+        let nodes: Vec<Vec<&str>> = lines
+            .skip(1)
+            .map(|line| {
+                line.split(|c: char| !c.is_ascii_alphanumeric())
+                    .filter(|name| !name.is_empty())
+                    .collect()
+            })
+            .collect();
+        let index = |name: &str| nodes.iter().position(|node| node[0] == name).unwrap();
+        let mut node = index("AAA");
+        for step in 0..=nodes.len() * directions.len() {
+            if nodes[node][0] == "ZZZ" {
+                return Some(step as u32);
+            }
+            let next = if directions[step % directions.len()] == b'L' {
+                1
+            } else {
+                2
+            };
+            node = index(nodes[node][next]);
+        }
+        None
+    }
+
+    /// `AAA`, `ZZZ` and up to six more nodes, each leading anywhere.
+    fn network() -> impl Strategy<Value = String> {
+        (
+            "[LR]{1,8}",
+            2..8usize,
+            prop::collection::vec((0..8usize, 0..8usize), 8),
+        )
+            .prop_map(|(directions, count, edges)| {
+                let name = |node: usize| match node {
+                    0 => "AAA".to_string(),
+                    1 => "ZZZ".to_string(),
+                    node => format!("N{:02}", node),
+                };
+                let nodes: Vec<String> = (0..count)
+                    .map(|node| {
+                        let (left, right) = edges[node];
+                        format!(
+                            "{} = ({}, {})",
+                            name(node),
+                            name(left % count),
+                            name(right % count)
+                        )
+                    })
+                    .collect();
+                format!("{}\n\n{}", directions, nodes.join("\n"))
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = parse_input(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "[LRAZ(), =\n]{0,32}") {
+            let _ = parse_input(&input);
+        }
+
+        #[test]
+        fn agrees_with_reference(input in network()) {
+            let expected = reference(&input);
+            prop_assume!(expected.is_some());
+            prop_assert_eq!(Some(run(&input)), expected);
+        }
+    }
 }
@@ -36,38 +36,60 @@ use std::collections::HashMap;
 
 use regex::Regex;
 
-fn parse_input(input: &str) -> (String, HashMap<String, (String, String)>) {
+/// Where each node leads, left and right.
+pub type Nodes = HashMap<String, (String, String)>;
+
+/// Reads the instructions on the first line and, after a blank line, one
+/// node per line as `11A = (11B, XXX)`. Every node a node leads to must be
+/// listed too.
+#[tracing::instrument(name = "parse", skip_all)]
+pub fn parse_input(input: &str) -> Result<(String, Nodes), String> {
     let lines: Vec<&str> = input.lines().collect();
-    let instructions = lines[0].to_string();
+    let instructions = match lines.first() {
+        Some(line) if !line.is_empty() && line.chars().all(|c| c == 'L' || c == 'R') => {
+            line.to_string()
+        }
+        _ => return Err("Expected L and R instructions on the first line".to_string()),
+    };
+    if lines.get(1).is_some_and(|line| !line.is_empty()) {
+        return Err("Expected a blank line after the instructions".to_string());
+    }
     let mut nodes = HashMap::new();
 
-    let regex = Regex::new(r"\w{3}").unwrap();
+    let regex = Regex::new(r"^(\w{3}) = \((\w{3}), (\w{3})\)$").unwrap();
     for line in lines.iter().skip(2) {
-        let captures = regex.find_iter(line);
-        let vec: Vec<&str> = captures.map(|m| m.as_str()).collect();
-        nodes.insert(vec[0].to_string(), (vec[1].to_string(), vec[2].to_string()));
+        let captures = regex
+            .captures(line)
+            .ok_or_else(|| format!("Failed to parse: {}", line))?;
+        nodes.insert(
+            captures[1].to_string(),
+            (captures[2].to_string(), captures[3].to_string()),
+        );
+    }
+    for (left, right) in nodes.values() {
+        for node in [left, right] {
+            if !nodes.contains_key(node) {
+                return Err(format!("Unknown node: {}", node));
+            }
+        }
     }
 
-    (instructions, nodes)
+    Ok((instructions, nodes))
 }
 
+#[tracing::instrument(skip(input))]
 pub fn run(input: &str) -> i64 {
-    let (instructions, nodes) = parse_input(input);
+    let (instructions, nodes) = parse_input(input).unwrap();
     get_steps(&nodes, &instructions, "..A", "..Z")
 }
 
-fn get_steps(
-    nodes: &HashMap<String, (String, String)>,
-    instructions: &str,
-    from: &str,
-    to: &str,
-) -> i64 {
+fn get_steps(nodes: &Nodes, instructions: &str, from: &str, to: &str) -> i64 {
     let current = filter_nodes(nodes, from);
     let steps = process_instructions(nodes, instructions, &current, to);
     lcm_list(&steps)
 }
 
-fn filter_nodes(nodes: &HashMap<String, (String, String)>, pattern: &str) -> Vec<String> {
+fn filter_nodes(nodes: &Nodes, pattern: &str) -> Vec<String> {
     let re = Regex::new(pattern).unwrap();
     nodes
         .keys()
@@ -77,7 +99,7 @@ fn filter_nodes(nodes: &HashMap<String, (String, String)>, pattern: &str) -> Vec
 }
 
 fn process_instructions(
-    nodes: &HashMap<String, (String, String)>,
+    nodes: &Nodes,
     instructions: &str,
     current: &[String],
     to: &str,
@@ -117,11 +139,13 @@ fn lcm(a: i64, b: i64) -> i64 {
 }
 
 fn lcm_list(list: &[i64]) -> i64 {
-    list.iter().copied().reduce(|a, b| lcm(a, b)).unwrap_or(0)
+    list.iter().copied().reduce(lcm).unwrap_or(0)
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -130,4 +154,81 @@ mod tests {
         let output = run(input);
         assert_eq!(output, 6);
     }
+
+    #[test]
+    fn invalid_input() {
+        assert!(parse_input("LR\n\n11A = (11Z, 11Z)\n11Z = (11Z, 11Z)").is_ok());
+        assert!(parse_input("").is_err());
+        assert!(parse_input("LX\n\n11A = (11A, 11A)").is_err());
+        assert!(parse_input("LR\n11A = (11A, 11A)").is_err());
+        assert!(parse_input("LR\n\n11A = (11A, 11B)").is_err());
+        assert!(parse_input("LR\n\n11A = (11A)").is_err());
+    }
+
+    /// Moves every ghost one step at a time until they all stand on a node
+    /// ending in `Z`.
+    fn reference(input: &str) -> i64 {
+        let mut lines = input.lines();
+        let instructions = lines.next().unwrap().as_bytes();
+        let nodes: HashMap<&str, (&str, &str)> = lines
+            .skip(1)
+            .map(|line| (&line[0..3], (&line[7..10], &line[12..15])))
+            .collect();
+        let mut ghosts: Vec<&str> = nodes
+            .keys()
+            .copied()
+            .filter(|node| node.ends_with('A'))
+            .collect();
+        let mut steps = 0;
+        while !ghosts.iter().all(|node| node.ends_with('Z')) {
+            let instruction = instructions[steps % instructions.len()];
+            for ghost in &mut ghosts {
+                let (left, right) = nodes[*ghost];
+                *ghost = if instruction == b'L' { left } else { right };
+            }
+            steps += 1;
+        }
+        steps as i64
+    }
+
+    /// Up to three ghosts, each on a loop whose `Z` node leads where its `A`
+    /// node does, as in the puzzle input, so that they all meet eventually.
+    fn network() -> impl Strategy<Value = String> {
+        ("[LR]{1,8}", prop::collection::vec(1..=8usize, 1..=3)).prop_map(
+            |(instructions, lengths)| {
+                let mut nodes = Vec::new();
+                for (ghost, length) in ["G", "H", "K"].into_iter().zip(lengths) {
+                    let name = |step: usize| match step {
+                        0 => format!("{}0A", ghost),
+                        step if step == length => format!("{}0Z", ghost),
+                        step => format!("{}{}M", ghost, step),
+                    };
+                    for step in 0..length {
+                        let next = name(step + 1);
+                        nodes.push(format!("{} = ({}, {})", name(step), next, next));
+                    }
+                    let next = name(1);
+                    nodes.push(format!("{} = ({}, {})", name(length), next, next));
+                }
+                format!("{}\n\n{}", instructions, nodes.join("\n"))
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = parse_input(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "[LR1AZ(), =\n]{0,32}") {
+            let _ = parse_input(&input);
+        }
+
+        #[test]
+        fn agrees_with_reference(input in network()) {
+            prop_assert_eq!(run(&input), reference(&input));
+        }
+    }
 }
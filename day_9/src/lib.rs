@@ -1,2 +1,55 @@
 pub mod part_one;
 pub mod part_two;
+
+/// Reads one history of whitespace separated values per line.
+#[tracing::instrument(name = "parse", skip_all)]
+pub fn parse_reports(input: &str) -> Result<Vec<Vec<i32>>, String> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let history = line
+                .split_whitespace()
+                .map(|number| {
+                    number
+                        .parse::<i32>()
+                        .map_err(|e| format!("line {}: {:?}: {}", index + 1, number, e))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if history.is_empty() {
+                return Err(format!("line {}: empty history", index + 1));
+            }
+            Ok(history)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn invalid_reports() {
+        assert_eq!(
+            parse_reports("0 3 6\n-1 2\n"),
+            Ok(vec![vec![0, 3, 6], vec![-1, 2]])
+        );
+        assert!(parse_reports("0 3 x").is_err());
+        assert!(parse_reports("0 3\n\n1 2").is_err());
+        assert!(parse_reports("99999999999").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "\\PC*") {
+            let _ = parse_reports(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_near_misses(input in "[0-9 \\-\n]{0,32}") {
+            let _ = parse_reports(&input);
+        }
+    }
+}
@@ -14,9 +14,9 @@ fn main() {
 
     let string = include_str!("../input.txt");
 
-    let part_one = part_one::run(string);
+    let part_one = part_one::run(string).unwrap();
     info!("Part One: {}", part_one);
 
-    let part_two = part_two::run(string);
+    let part_two = part_two::run(string).unwrap();
     info!("Part Two: {}", part_two);
 }
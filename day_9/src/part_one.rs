@@ -64,15 +64,20 @@ If you find the next value for each history in this example and add them togethe
 
 Analyze your OASIS report and extrapolate the next value for each history. What is the sum of these extrapolated values?
 */
-fn get_next_value(history: &[i32]) -> i32 {
+
+use crate::parse_reports;
+
+/// The next value of `history`, or `None` when a difference or the value
+/// itself overflows an `i32`.
+fn get_next_value(history: &[i32]) -> Option<i32> {
     let mut sequences = vec![history.to_vec()];
 
     loop {
         let last_sequence = sequences.last().unwrap();
-        let diffs: Vec<i32> = last_sequence
+        let diffs = last_sequence
             .windows(2)
-            .map(|window| window[1] - window[0])
-            .collect();
+            .map(|window| window[1].checked_sub(window[0]))
+            .collect::<Option<Vec<i32>>>()?;
 
         if diffs.iter().all(|&x| x == 0) {
             break;
@@ -84,33 +89,33 @@ fn get_next_value(history: &[i32]) -> i32 {
     let mut next_value = *sequences[0].last().unwrap();
 
     for sequence in sequences.iter().skip(1) {
-        next_value += sequence.last().unwrap();
+        next_value = next_value.checked_add(*sequence.last().unwrap())?;
     }
 
-    next_value
+    Some(next_value)
 }
 
-fn sum_of_next_values(reports: &[Vec<i32>]) -> i32 {
-    reports.iter().map(|history| get_next_value(history)).sum()
+fn sum_of_next_values(reports: &[Vec<i32>]) -> Result<i32, String> {
+    reports
+        .iter()
+        .enumerate()
+        .try_fold(0i32, |sum, (index, history)| {
+            get_next_value(history)
+                .and_then(|next| sum.checked_add(next))
+                .ok_or_else(|| format!("line {}: the next value overflows an i32", index + 1))
+        })
 }
 
 #[tracing::instrument(skip(input))]
-pub fn run(input: &str) -> i32 {
-    let reports: Vec<Vec<i32>> = input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|number| number.parse::<i32>().unwrap())
-                .collect()
-        })
-        .collect();
+pub fn run(input: &str) -> Result<i32, String> {
+    let reports = parse_reports(input)?;
 
-    let result = sum_of_next_values(&reports);
-    result
+    sum_of_next_values(&reports)
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -118,6 +123,65 @@ mod tests {
     fn part_1() {
         let input = include_str!("../test_data.txt");
         let output = run(input);
-        assert_eq!(output, 114);
+        assert_eq!(output, Ok(114));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            run("0 1 2\n2147483647 -2147483648"),
+            Err("line 2: the next value overflows an i32".to_string())
+        );
+        assert_eq!(
+            run("2147483646 2147483647"),
+            Err("line 1: the next value overflows an i32".to_string())
+        );
+        assert_eq!(
+            run("2147483647\n1"),
+            Err("line 2: the next value overflows an i32".to_string())
+        );
+    }
+
+    /// Extrapolates the next value of each history directly, as the sum of
+    /// its values weighted by alternating binomial coefficients.
+    fn reference(input: &str) -> i64 {
+        input
+            .lines()
+            .map(|line| {
+                let values: Vec<i64> = line
+                    .split_whitespace()
+                    .map(|value| value.parse().unwrap())
+                    .collect();
+                let n = values.len();
+                let mut coefficient = 1i64;
+                let mut next = 0;
+                // Walks from the last value back: C(n, 1), -C(n, 2), ...
+                for (k, value) in values.iter().rev().enumerate() {
+                    coefficient = coefficient * (n - k) as i64 / (k + 1) as i64;
+                    let sign = if k % 2 == 0 { 1 } else { -1 };
+                    next += sign * coefficient * value;
+                }
+                next
+            })
+            .sum()
+    }
+
+    /// Short histories of small values, so that no difference overflows.
+    fn reports() -> impl Strategy<Value = String> {
+        let history = prop::collection::vec(-100..100i32, 1..12).prop_map(|values| {
+            values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        prop::collection::vec(history, 1..16).prop_map(|histories| histories.join("\n"))
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in reports()) {
+            prop_assert_eq!(run(&input).map(i64::from), Ok(reference(&input)));
+        }
     }
 }
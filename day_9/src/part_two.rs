@@ -18,51 +18,54 @@ Doing this for the remaining example data above results in previous values of -3
 Analyze your OASIS report again, this time extrapolating the previous value for each history. What is the sum of these extrapolated values?
 
 */
-fn get_previous_value(history: &[i32]) -> i32 {
+
+use crate::parse_reports;
+
+/// The previous value of `history`, or `None` when a difference or the value
+/// itself overflows an `i32`.
+fn get_previous_value(history: &[i32]) -> Option<i32> {
     let mut sequences = vec![history.to_vec()];
 
     while sequences.last().unwrap().iter().any(|&x| x != 0) {
         let last_sequence = sequences.last().unwrap();
-        let diffs: Vec<i32> = last_sequence
+        let diffs = last_sequence
             .windows(2)
-            .map(|window| window[1] - window[0])
-            .collect();
+            .map(|window| window[1].checked_sub(window[0]))
+            .collect::<Option<Vec<i32>>>()?;
         sequences.push(diffs);
     }
 
     for i in (1..sequences.len()).rev() {
-        let diff = sequences[i][0];
-        let new_value = sequences[i - 1][0] - diff;
+        // A single value has no differences, which extrapolate as zero.
+        let diff = sequences[i].first().copied().unwrap_or(0);
+        let new_value = sequences[i - 1][0].checked_sub(diff)?;
         sequences[i - 1].insert(0, new_value);
     }
 
-    sequences[0][0]
+    Some(sequences[0][0])
 }
 
-fn sum_of_previous_values(reports: &[Vec<i32>]) -> i32 {
+fn sum_of_previous_values(reports: &[Vec<i32>]) -> Result<i32, String> {
     reports
         .iter()
-        .map(|history| get_previous_value(history))
-        .sum()
+        .enumerate()
+        .try_fold(0i32, |sum, (index, history)| {
+            get_previous_value(history)
+                .and_then(|previous| sum.checked_add(previous))
+                .ok_or_else(|| format!("line {}: the previous value overflows an i32", index + 1))
+        })
 }
 
 #[tracing::instrument(skip(input))]
-pub fn run(input: &str) -> i32 {
-    let reports: Vec<Vec<i32>> = input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|number| number.parse::<i32>().unwrap())
-                .collect()
-        })
-        .collect();
+pub fn run(input: &str) -> Result<i32, String> {
+    let reports = parse_reports(input)?;
 
-    let result = sum_of_previous_values(&reports);
-    result
+    sum_of_previous_values(&reports)
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -70,6 +73,65 @@ mod tests {
     fn part_2() {
         let input = include_str!("../test_data.txt");
         let output = run(input);
-        assert_eq!(output, 2);
+        assert_eq!(output, Ok(2));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            run("0 1 2\n2147483647 -2147483648"),
+            Err("line 2: the previous value overflows an i32".to_string())
+        );
+        assert_eq!(
+            run("-2147483647 -2147483645"),
+            Err("line 1: the previous value overflows an i32".to_string())
+        );
+        assert_eq!(
+            run("-2147483648\n-1"),
+            Err("line 2: the previous value overflows an i32".to_string())
+        );
+    }
+
+    /// Extrapolates the previous value of each history directly, as the sum
+    /// of its values weighted by alternating binomial coefficients.
+    fn reference(input: &str) -> i64 {
+        input
+            .lines()
+            .map(|line| {
+                let values: Vec<i64> = line
+                    .split_whitespace()
+                    .map(|value| value.parse().unwrap())
+                    .collect();
+                let n = values.len();
+                let mut coefficient = 1i64;
+                let mut previous = 0;
+                // Walks from the first value on: C(n, 1), -C(n, 2), ...
+                for (k, value) in values.iter().enumerate() {
+                    coefficient = coefficient * (n - k) as i64 / (k + 1) as i64;
+                    let sign = if k % 2 == 0 { 1 } else { -1 };
+                    previous += sign * coefficient * value;
+                }
+                previous
+            })
+            .sum()
+    }
+
+    /// Short histories of small values, so that no difference overflows.
+    fn reports() -> impl Strategy<Value = String> {
+        let history = prop::collection::vec(-100..100i32, 1..12).prop_map(|values| {
+            values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        prop::collection::vec(history, 1..16).prop_map(|histories| histories.join("\n"))
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in reports()) {
+            prop_assert_eq!(run(&input).map(i64::from), Ok(reference(&input)));
+        }
     }
 }
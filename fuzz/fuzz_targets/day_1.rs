@@ -0,0 +1,13 @@
+#![no_main]
+
+use day_1::{part_two::NUMBERS, scanner::Scanner};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let digits = Scanner::digits();
+    let words = Scanner::new(NUMBERS);
+    for line in day_1::lines(input) {
+        let _ = digits.calibration_value(line);
+        let _ = words.calibration_value(line);
+    }
+});
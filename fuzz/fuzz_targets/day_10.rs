@@ -0,0 +1,11 @@
+#![no_main]
+
+use day_10::{parse_sketch, part_one, part_two};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if parse_sketch(input).is_ok() {
+        part_one::run(input);
+        part_two::run(input);
+    }
+});
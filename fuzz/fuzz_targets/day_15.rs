@@ -0,0 +1,9 @@
+#![no_main]
+
+use day_15::{part_one, part_two::Step};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    part_one::run(input);
+    let _ = Step::parse_all(input);
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use day_16::{part_one, part_two};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if input.parse::<part_one::Grid>().is_ok() {
+        part_one::run(input);
+    }
+    if input.parse::<part_two::Grid>().is_ok() {
+        part_two::run(input);
+    }
+});
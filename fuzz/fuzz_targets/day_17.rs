@@ -0,0 +1,9 @@
+#![no_main]
+
+use day_17::{part_one, part_two};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<part_one::Grid>();
+    part_two::Grid::parse_grid(input);
+});
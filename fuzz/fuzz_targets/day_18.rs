@@ -0,0 +1,8 @@
+#![no_main]
+
+use day_18::part_one;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = part_one::run(input);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use day_2::{bag::Bag, game::Game, part_one::PartOne, part_two::PartTwo};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(games) = Game::parse_all(input) {
+        let _ = PartOne::new(&games).run();
+        let _ = PartTwo::new(&games).run();
+    }
+    let _ = input.parse::<Bag>();
+});
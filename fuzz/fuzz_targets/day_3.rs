@@ -0,0 +1,11 @@
+#![no_main]
+
+use day_3::{part_one, part_two, schematic::Schematic};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if input.parse::<Schematic>().is_ok() {
+        let _ = part_one::run(input);
+        let _ = part_two::run(input);
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use day_4::{card::Card, explain, part_one::PartOne, part_two::PartTwo};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let lines: Vec<&str> = input.lines().collect();
+    if let Ok(cards) = Card::parse_all(&lines) {
+        let _ = PartOne::new(&cards).run();
+        let _ = PartTwo::new(&cards).run();
+        let _ = explain::breakdown(&cards);
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use day_5::{almanac::Almanac, part_one::PartOne, part_two::PartTwo};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(almanac) = input.parse::<Almanac>() {
+        let _ = almanac.lint();
+        let _ = almanac.function("seed", "location");
+        let _ = PartOne::new(input).run();
+        let _ = PartTwo::new(input).run();
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use day_6::{
+    part_one::PartOne,
+    part_two::PartTwo,
+    race::{parse_races, Kerning},
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if parse_races(input, Kerning::Separate).is_ok() {
+        PartOne::new(input).run();
+    }
+    if parse_races(input, Kerning::Single).is_ok() {
+        PartTwo::new(input).run();
+    }
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use day_7::{part_one::PartOne, part_two::PartTwo};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = PartOne::new(input);
+    let _ = PartTwo::new(input);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use day_8::{part_one, part_two};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = part_one::parse_input(input);
+    let _ = part_two::parse_input(input);
+});
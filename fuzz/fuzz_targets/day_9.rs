@@ -0,0 +1,11 @@
+#![no_main]
+
+use day_9::{parse_reports, part_one, part_two};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if parse_reports(input).is_ok() {
+        let _ = part_one::run(input);
+        let _ = part_two::run(input);
+    }
+});
@@ -0,0 +1,167 @@
+//! Heap counters kept by a global allocator wrapping the system one. The
+//! allocator is only installed with the `count-allocations` feature, and
+//! counts only once [`enable`] is called, so that timings are not skewed
+//! otherwise.
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+/// What was allocated between a [`reset`] and a [`snapshot`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Allocations {
+    /// Calls to `alloc` and `realloc`.
+    pub count: u64,
+    /// Bytes requested by those calls.
+    pub bytes: u64,
+    /// The most bytes live at once, above what was live at the reset.
+    pub peak: u64,
+}
+
+impl fmt::Display for Allocations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>9} allocs {:>12} B allocated {:>12} B peak",
+            self.count, self.bytes, self.peak
+        )
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static COUNT: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static LIVE: AtomicU64 = AtomicU64::new(0);
+static PEAK: AtomicU64 = AtomicU64::new(0);
+static BASELINE: AtomicU64 = AtomicU64::new(0);
+
+/// Whether the runner was built with the counting allocator.
+pub const AVAILABLE: bool = cfg!(feature = "count-allocations");
+
+/// Starts counting. Fails when the counting allocator is not installed.
+pub fn enable() -> Result<(), String> {
+    if !AVAILABLE {
+        return Err("allocations are not counted, build with --features count-allocations".into());
+    }
+    ENABLED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Zeroes the counters and makes what is live now the baseline of the peak.
+pub fn reset() {
+    let live = LIVE.load(Ordering::Relaxed);
+    COUNT.store(0, Ordering::Relaxed);
+    BYTES.store(0, Ordering::Relaxed);
+    BASELINE.store(live, Ordering::Relaxed);
+    PEAK.store(live, Ordering::Relaxed);
+}
+
+/// The counters since the last [`reset`], if counting is enabled.
+pub fn snapshot() -> Option<Allocations> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(Allocations {
+        count: COUNT.load(Ordering::Relaxed),
+        bytes: BYTES.load(Ordering::Relaxed),
+        peak: PEAK
+            .load(Ordering::Relaxed)
+            .saturating_sub(BASELINE.load(Ordering::Relaxed)),
+    })
+}
+
+/// Held by tests that reset the counters, which are shared by all threads.
+#[cfg(test)]
+pub(crate) static TEST_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(feature = "count-allocations")]
+mod counting {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::Ordering,
+    };
+
+    use super::{BYTES, COUNT, ENABLED, LIVE, PEAK};
+
+    // Live bytes are tracked even while disabled, so that frees of memory
+    // allocated before `enable` do not underflow.
+    fn allocated(size: usize) {
+        let live = LIVE.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+        if ENABLED.load(Ordering::Relaxed) {
+            COUNT.fetch_add(1, Ordering::Relaxed);
+            BYTES.fetch_add(size as u64, Ordering::Relaxed);
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+    }
+
+    fn freed(size: usize) {
+        LIVE.fetch_sub(size as u64, Ordering::Relaxed);
+    }
+
+    struct Counting;
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let pointer = System.alloc(layout);
+            if !pointer.is_null() {
+                allocated(layout.size());
+            }
+            pointer
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let pointer = System.alloc_zeroed(layout);
+            if !pointer.is_null() {
+                allocated(layout.size());
+            }
+            pointer
+        }
+
+        unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+            System.dealloc(pointer, layout);
+            freed(layout.size());
+        }
+
+        unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let moved = System.realloc(pointer, layout, new_size);
+            if !moved.is_null() {
+                freed(layout.size());
+                allocated(new_size);
+            }
+            moved
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(feature = "count-allocations"))]
+    fn test_unavailable() {
+        assert!(enable().is_err());
+        assert_eq!(snapshot(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "count-allocations")]
+    fn test_counting() {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        enable().unwrap();
+        reset();
+        let buffer = std::hint::black_box(vec![0u8; 1 << 20]);
+        let grown = std::hint::black_box(Vec::<u64>::with_capacity(1 << 16));
+        drop(buffer);
+        drop(grown);
+        // Other tests still allocate concurrently, so only lower bounds hold.
+        let allocations = snapshot().unwrap();
+        assert!(allocations.count >= 2);
+        assert!(allocations.bytes >= (1 << 20) + (8 << 16));
+        assert!(allocations.peak >= (1 << 20) + (8 << 16));
+    }
+}
@@ -0,0 +1,92 @@
+use std::{fs, io, path::Path};
+
+use serde::Deserialize;
+
+/// The answers accepted for a day, read from the `answers.toml` beside its
+/// `input.txt`:
+///
+/// ```toml
+/// part_one = 55488
+/// part_two = "55614"
+/// ```
+///
+/// Either part may be missing. Answers too large for a TOML integer are
+/// written as strings.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Answers {
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Raw {
+    part_one: Option<toml::Value>,
+    part_two: Option<toml::Value>,
+}
+
+fn value(value: Option<toml::Value>) -> Result<Option<String>, String> {
+    match value {
+        None => Ok(None),
+        Some(toml::Value::String(value)) => Ok(Some(value)),
+        Some(toml::Value::Integer(value)) => Ok(Some(value.to_string())),
+        Some(value) => Err(format!(
+            "expected an integer or a string, found {}",
+            value.type_str()
+        )),
+    }
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let raw: Raw = toml::from_str(input).map_err(|e| e.to_string())?;
+        Ok(Self {
+            part_one: value(raw.part_one)?,
+            part_two: value(raw.part_two)?,
+        })
+    }
+
+    /// Reads `path`, treating a missing file as no recorded answers.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(input) => Self::parse(&input).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_one.as_deref(),
+            2 => self.part_two.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("part_one = 55488\npart_two = \"55614\"\n").unwrap();
+        assert_eq!(answers.get(1), Some("55488"));
+        assert_eq!(answers.get(2), Some("55614"));
+
+        let answers = Answers::parse("part_one = 1\n").unwrap();
+        assert_eq!(answers.get(2), None);
+
+        assert!(Answers::parse("part_one = 1.5\n").is_err());
+        assert!(Answers::parse("part_three = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        assert_eq!(
+            Answers::load("./does_not_exist.toml"),
+            Ok(Answers::default())
+        );
+    }
+}
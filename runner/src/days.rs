@@ -0,0 +1,142 @@
+use std::{
+    any::type_name,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// What a part produced, kept as text so every day can be compared alike.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Answer {
+    pub value: String,
+    /// The Rust type the part returned, e.g. `u32` or `num_bigint::BigUint`.
+    pub kind: &'static str,
+}
+
+impl Answer {
+    pub fn new<T: fmt::Display>(value: T) -> Self {
+        Self {
+            value: value.to_string(),
+            kind: type_name::<T>(),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.value)
+    }
+}
+
+pub type Solve = fn(&str) -> Result<Answer, String>;
+
+/// A puzzle day and the parts solved so far.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub day: u8,
+    pub part_one: Option<Solve>,
+    pub part_two: Option<Solve>,
+}
+
+impl Day {
+    /// The crate directory of this day below the repository root.
+    pub fn directory(&self, root: impl AsRef<Path>) -> PathBuf {
+        root.as_ref().join(format!("day_{}", self.day))
+    }
+
+    /// `(part, solve)` for every implemented part.
+    pub fn parts(&self) -> impl Iterator<Item = (u8, Solve)> {
+        [(1, self.part_one), (2, self.part_two)]
+            .into_iter()
+            .filter_map(|(part, solve)| solve.map(|solve| (part, solve)))
+    }
+}
+
+impl fmt::Debug for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Day")
+            .field("day", &self.day)
+            .field("part_one", &self.part_one.is_some())
+            .field("part_two", &self.part_two.is_some())
+            .finish()
+    }
+}
+
+/// Every day of the repository the runner knows how to solve.
+pub const DAYS: &[Day] = &[
+    Day {
+        day: 1,
+        part_one: Some(|input| Ok(Answer::new(day_1::part_one::PartOne::new(input).run()))),
+        part_two: Some(|input| day_1::part_two::PartTwo::new(input).run().map(Answer::new)),
+    },
+    Day {
+        day: 2,
+        part_one: Some(|input| day_2::part_one::run(input).map(Answer::new)),
+        part_two: Some(|input| day_2::part_two::run(input).map(Answer::new)),
+    },
+    Day {
+        day: 3,
+        part_one: Some(|input| day_3::part_one::run(input).map(Answer::new)),
+        part_two: Some(|input| day_3::part_two::run(input).map(Answer::new)),
+    },
+    Day {
+        day: 4,
+        part_one: Some(|input| day_4::part_one::run(input).map(Answer::new)),
+        part_two: Some(|input| day_4::part_two::run(input).map(Answer::new)),
+    },
+    Day {
+        day: 5,
+        part_one: Some(|input| day_5::part_one::PartOne::new(input).run().map(Answer::new)),
+        part_two: Some(|input| day_5::part_two::PartTwo::new(input).run().map(Answer::new)),
+    },
+    Day {
+        day: 6,
+        part_one: Some(|input| Ok(Answer::new(day_6::part_one::PartOne::new(input).run()))),
+        part_two: Some(|input| Ok(Answer::new(day_6::part_two::PartTwo::new(input).run()))),
+    },
+    Day {
+        day: 7,
+        part_one: Some(|input| day_7::part_one::run(input).map(Answer::new)),
+        part_two: Some(|input| day_7::part_two::run(input).map(Answer::new)),
+    },
+    Day {
+        day: 8,
+        part_one: Some(|input| Ok(Answer::new(day_8::part_one::run(input)))),
+        part_two: Some(|input| Ok(Answer::new(day_8::part_two::run(input)))),
+    },
+    Day {
+        day: 9,
+        part_one: Some(|input| day_9::part_one::run(input).map(Answer::new)),
+        part_two: Some(|input| day_9::part_two::run(input).map(Answer::new)),
+    },
+    Day {
+        day: 10,
+        part_one: Some(|input| Ok(Answer::new(day_10::part_one::run(input)))),
+        part_two: Some(|input| Ok(Answer::new(day_10::part_two::run(input)))),
+    },
+    Day {
+        day: 15,
+        part_one: Some(|input| Ok(Answer::new(day_15::part_one::run(input)))),
+        part_two: Some(|input| Ok(Answer::new(day_15::part_two::run(input)))),
+    },
+    Day {
+        day: 16,
+        part_one: Some(|input| Ok(Answer::new(day_16::part_one::run(input)))),
+        part_two: Some(|input| Ok(Answer::new(day_16::part_two::run(input)))),
+    },
+    Day {
+        day: 17,
+        part_one: Some(|input| Ok(Answer::new(day_17::part_one::run(input)))),
+        part_two: Some(|input| Ok(Answer::new(day_17::part_two::run(input)))),
+    },
+    Day {
+        day: 18,
+        part_one: Some(|input| day_18::part_one::run(input).map(Answer::new)),
+        // Still the stub returning `0`.
+        part_two: None,
+    },
+];
+
+/// The registered day `day`, if any.
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|registered| registered.day == day)
+}
@@ -0,0 +1,412 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{days::Day, verify};
+
+/// An example input from a puzzle statement and the answer the statement
+/// gives for it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Example {
+    pub part: u8,
+    pub answer: String,
+    /// Whether the solution agreed with `answer` when the example was
+    /// extracted. Examples it disagrees with are kept for reference, but they
+    /// are not tested.
+    pub confirmed: bool,
+    pub input: String,
+}
+
+/// The examples of a day as written to its `examples.toml`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Examples {
+    #[serde(default, rename = "example")]
+    pub examples: Vec<Example>,
+}
+
+impl Examples {
+    /// Reads `path`, treating a missing file as no examples.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(input) => toml::from_str(&input).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let output = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, output).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// The block comment a part's source starts with, without the comment
+/// markers and the ` * ` some days prefix every line with.
+pub fn statement(source: &str) -> Option<String> {
+    let body = source.trim_start().strip_prefix("/*")?;
+    let end = body.find("*/")?;
+    let lines: Vec<&str> = body[..end].lines().collect();
+    let starred = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .all(|line| line.trim_start().starts_with('*'));
+    let lines: Vec<&str> = lines
+        .into_iter()
+        .map(|line| {
+            if starred {
+                let line = line.trim_start().trim_start_matches('*');
+                line.strip_prefix(' ').unwrap_or(line)
+            } else {
+                line
+            }
+        })
+        .collect();
+    Some(lines.join("\n").trim().to_string())
+}
+
+const STOPWORDS: [&str; 10] = [
+    "the", "is", "a", "you", "and", "of", "to", "that", "this", "in",
+];
+
+/// Whether `line` reads like a sentence rather than like puzzle input.
+fn is_prose(line: &str) -> bool {
+    let line = line.trim();
+    let words: Vec<&str> = line.split_whitespace().collect();
+    line.starts_with("---")
+        || (words.len() >= 3 && line.ends_with(['.', '?', '!', ':']))
+        || (words.len() >= 2 && line.ends_with(':') && line.starts_with(char::is_uppercase))
+        || (words.len() >= 4
+            && words
+                .iter()
+                .any(|word| STOPWORDS.contains(&word.to_lowercase().as_str())))
+}
+
+/// A block of input-like lines, with the sentence that introduces it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Block {
+    /// The line of the statement the introduction is on, from zero.
+    pub line: usize,
+    pub intro: String,
+    pub input: String,
+}
+
+/// Every block of input-like lines that directly follows a sentence ending
+/// in a colon. Blank lines inside a block are kept.
+pub fn blocks(statement: &str) -> Vec<Block> {
+    let lines: Vec<&str> = statement.lines().collect();
+    let mut blocks = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let intro = index;
+        let line = lines[intro].trim();
+        index += 1;
+        if !(is_prose(line) && line.ends_with(':')) {
+            continue;
+        }
+        while index < lines.len() && lines[index].trim().is_empty() {
+            index += 1;
+        }
+        let start = index;
+        let mut end = index;
+        while index < lines.len() {
+            let current = lines[index];
+            if current.trim().is_empty() {
+                index += 1;
+                continue;
+            }
+            if is_prose(current) {
+                break;
+            }
+            index += 1;
+            end = index;
+        }
+        if end > start {
+            let input: Vec<&str> = lines[start..end]
+                .iter()
+                .map(|line| line.trim_end())
+                .collect();
+            blocks.push(Block {
+                line: intro,
+                intro: line.to_string(),
+                input: input.join("\n") + "\n",
+            });
+        }
+        index = end.max(start);
+    }
+    blocks
+}
+
+/// The last integer in the prose before the closing question, which is where
+/// the statements give the answer for the example. Numbers in parentheses
+/// are working, not answers, and are skipped.
+pub fn answer(statement: &str) -> Option<String> {
+    answer_line(statement).map(|(_, answer)| answer)
+}
+
+/// The answer with the line it is on, from zero.
+fn answer_line(statement: &str) -> Option<(usize, String)> {
+    let lines: Vec<(usize, &str)> = statement
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .collect();
+    let question = lines
+        .iter()
+        .rposition(|(_, line)| line.ends_with('?') || line.contains("? "))
+        .unwrap_or(lines.len());
+    lines[..question]
+        .iter()
+        .rev()
+        .filter(|(_, line)| is_prose(line))
+        .find_map(|&(index, line)| {
+            let mut depth = 0;
+            let outside: String = line
+                .chars()
+                .map(|c| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ if depth > 0 => return ' ',
+                        _ => {}
+                    }
+                    c
+                })
+                .collect();
+            outside
+                .split_whitespace()
+                .rev()
+                .map(|word| word.trim_matches(|c: char| ",.;:!?()\"'".contains(c)))
+                .find(|word| word.parse::<i64>().is_ok())
+                .map(|word| (index, word.to_string()))
+        })
+}
+
+/// Whether `intro` brings in an example of its own, as in "For example:" or
+/// "Here's another example:", rather than going back to one with "the above
+/// example".
+fn introduces_example(intro: &str) -> bool {
+    let words: Vec<String> = intro
+        .split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .collect();
+    words.iter().enumerate().any(|(index, word)| {
+        word.starts_with("example")
+            && words[index.saturating_sub(3)..index]
+                .iter()
+                .rev()
+                .find(|word| ["the", "for", "a", "an", "another"].contains(&word.as_str()))
+                .is_some_and(|word| word != "the")
+    })
+}
+
+/// The block a statement gives its answer for: the last one before the
+/// sentence with the answer that is introduced as an example, or that goes
+/// back to an example by quoting an earlier block again. Other blocks show
+/// the working on an example.
+fn example(blocks: &[Block], answer_line: usize) -> Option<&Block> {
+    blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, block)| block.line <= answer_line)
+        .rfind(|&(index, block)| {
+            introduces_example(&block.intro)
+                || (block.intro.to_lowercase().contains("example")
+                    && blocks[..index]
+                        .iter()
+                        .any(|earlier| earlier.input == block.input))
+        })
+        .map(|(_, block)| block)
+}
+
+/// Extracts one example per solved part from the statements at the top of
+/// the day's `part_one.rs` and `part_two.rs`.
+///
+/// The example is picked by its place in the statement, whatever the
+/// solution makes of it. Part two reuses the example of part one when its
+/// statement brings in none of its own.
+pub fn extract(directory: &Path, day: &Day) -> Result<Vec<Example>, String> {
+    let mut examples = Vec::new();
+    let mut earlier: Option<Block> = None;
+    for (part, file) in [(1, "part_one.rs"), (2, "part_two.rs")] {
+        let path = directory.join("src").join(file);
+        let source = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let statement = statement(&source);
+        let answer = statement.as_deref().and_then(answer_line);
+        let own = match (&statement, &answer) {
+            (Some(statement), Some((line, _))) => example(&blocks(statement), *line).cloned(),
+            _ => None,
+        };
+        let block = own.or(earlier.take());
+        earlier = block.clone();
+
+        // Nothing to check an unsolved part's example against.
+        let Some((_, solve)) = day.parts().find(|&(solved, _)| solved == part) else {
+            continue;
+        };
+        let (Some((_, answer)), Some(block)) = (answer, block) else {
+            return Err(format!("part {}: no example in {}", part, path.display()));
+        };
+        let confirmed =
+            matches!(verify::run(solve, &block.input).0, Ok(found) if found.value == answer);
+        examples.push(Example {
+            part,
+            answer,
+            confirmed,
+            input: block.input,
+        });
+    }
+    Ok(examples)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::days::DAYS;
+
+    use super::*;
+
+    const STATEMENT: &str = "\
+--- Day 1: Trebuchet?! ---
+The newly-improved calibration document consists of lines of text.
+For example:
+1abc2
+pqr3stu8vwx
+
+a1b2c3d4e5f
+In this example, the calibration values are 12, 38 and 15. Adding these together produces 65.
+Consider your entire calibration document. What is the sum of all of the calibration values?";
+
+    #[test]
+    fn test_statement() {
+        let source = "/*\n * --- Day 1 ---\n * For example:\n * 1abc2\n */\nuse std::io;\n";
+        assert_eq!(
+            statement(source).unwrap(),
+            "--- Day 1 ---\nFor example:\n1abc2"
+        );
+        assert_eq!(statement("use std::io;\n"), None);
+    }
+
+    #[test]
+    fn test_blocks_and_answer() {
+        assert_eq!(
+            blocks(STATEMENT),
+            [Block {
+                line: 2,
+                intro: "For example:".to_string(),
+                input: "1abc2\npqr3stu8vwx\n\na1b2c3d4e5f\n".to_string(),
+            }]
+        );
+        assert_eq!(answer(STATEMENT), Some("65".to_string()));
+        assert_eq!(
+            answer("If you multiply these together, you get 288 (4 * 8 * 9).\nWhat do you get?"),
+            Some("288".to_string())
+        );
+    }
+
+    #[test]
+    fn test_example() {
+        let statement = "\
+For example:
+RL
+
+AAA = (BBB, BBB)
+In the above example, here is how the steps go:
+AAA BBB AAA
+Here's another example that takes 6 steps:
+LLR
+
+AAA = (ZZZ, ZZZ)
+Here is the same example again:
+RL
+
+AAA = (BBB, BBB)
+What is the answer?";
+        let blocks = blocks(statement);
+        let (line, answer) = answer_line(statement).unwrap();
+        assert_eq!((line, answer.as_str()), (6, "6"));
+        assert_eq!(example(&blocks, line), Some(&blocks[2]));
+        assert_eq!(example(&blocks, 12), Some(&blocks[3]));
+        assert_eq!(example(&blocks[1..2], line), None);
+
+        assert!(introduces_example("For example:"));
+        assert!(introduces_example("Here's a larger example:"));
+        assert!(!introduces_example("So, the example from before:"));
+        assert!(!introduces_example(
+            "In the above example, it goes like this:"
+        ));
+    }
+
+    #[test]
+    fn test_missing_example() {
+        let directory = env::temp_dir().join(format!("runner-examples-{}", std::process::id()));
+        fs::remove_dir_all(&directory).ok();
+        fs::create_dir_all(directory.join("src")).unwrap();
+        fs::write(
+            directory.join("src/part_one.rs"),
+            format!("/*\n{}\n*/\n", STATEMENT),
+        )
+        .unwrap();
+        fs::write(directory.join("src/part_two.rs"), "pub fn run() {}\n").unwrap();
+        let day = crate::days::find(1).unwrap();
+
+        let error = extract(&directory, day).unwrap_err();
+        assert!(error.starts_with("part 2: no example in "), "{}", error);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_prose() {
+        assert!(is_prose("Here is an example engine schematic:"));
+        assert!(is_prose("| is a vertical pipe connecting north and south."));
+        assert!(!is_prose(
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+        ));
+        assert!(is_prose("For example:"));
+        assert!(!is_prose("seed-to-soil map:"));
+        assert!(!is_prose("467..114.."));
+        assert!(!is_prose("AAA = (BBB, CCC)"));
+    }
+
+    /// Runs every day's solution on the examples in its `examples.toml`.
+    #[test]
+    fn test_fixtures() {
+        for day in DAYS {
+            let directory = day.directory(crate::root());
+            let fixtures = Examples::load(directory.join("examples.toml")).unwrap();
+            for example in fixtures.examples.iter().filter(|example| example.confirmed) {
+                let (_, solve) = day.parts().find(|&(part, _)| part == example.part).unwrap();
+                let (found, _) = verify::run(solve, &example.input);
+                assert_eq!(
+                    found.map(|answer| answer.value),
+                    Ok(example.answer.clone()),
+                    "day {} part {}",
+                    day.day,
+                    example.part
+                );
+            }
+        }
+    }
+
+    /// The committed fixtures are what the statements currently yield.
+    #[test]
+    fn test_fixtures_are_current() {
+        for day in DAYS {
+            let directory = day.directory(crate::root());
+            let fixtures = Examples::load(directory.join("examples.toml")).unwrap();
+            assert_eq!(
+                extract(&directory, day).unwrap(),
+                fixtures.examples,
+                "day {}, regenerate with `runner examples {}`",
+                day.day,
+                day.day
+            );
+        }
+    }
+}
@@ -0,0 +1,1012 @@
+use std::collections::HashSet;
+
+/// SplitMix64, so that a seed gives the same input on every platform and
+/// with every version of the runner.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `low..high`, which must not be empty.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        debug_assert!(low < high, "empty range {}..{}", low, high);
+        low + self.next_u64() % (high - low)
+    }
+
+    /// Whether an event with the given odds in a hundred happens.
+    pub fn percent(&mut self, odds: u64) -> bool {
+        self.range(0, 100) < odds
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0, i as u64 + 1) as usize);
+        }
+    }
+}
+
+/// A generated input with the answers its construction gives away, if any.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Generated {
+    pub input: String,
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+impl Generated {
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_one.as_deref(),
+            2 => self.part_two.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+pub struct Generator {
+    pub day: u8,
+    /// A size that gives inputs about as large as the real ones.
+    pub size: usize,
+    pub generate: fn(usize, &mut Rng) -> Generated,
+}
+
+pub const GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        size: 1000,
+        generate: calibration_document,
+    },
+    Generator {
+        day: 2,
+        size: 100,
+        generate: cube_games,
+    },
+    Generator {
+        day: 3,
+        size: 140,
+        generate: engine_schematic,
+    },
+    Generator {
+        day: 4,
+        size: 200,
+        generate: scratchcards,
+    },
+    Generator {
+        day: 5,
+        size: 40,
+        generate: almanac,
+    },
+    Generator {
+        day: 6,
+        size: 4,
+        generate: races,
+    },
+    Generator {
+        day: 7,
+        size: 1000,
+        generate: camel_cards,
+    },
+    Generator {
+        day: 8,
+        size: 20,
+        generate: network,
+    },
+    Generator {
+        day: 9,
+        size: 200,
+        generate: oasis_report,
+    },
+    Generator {
+        day: 10,
+        size: 140,
+        generate: pipe_loop,
+    },
+    Generator {
+        day: 15,
+        size: 4000,
+        generate: initialization_sequence,
+    },
+    Generator {
+        day: 16,
+        size: 110,
+        generate: contraption,
+    },
+    Generator {
+        day: 17,
+        size: 141,
+        generate: heat_map,
+    },
+    Generator {
+        day: 18,
+        size: 60,
+        generate: dig_plan,
+    },
+];
+
+pub fn find(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|generator| generator.day == day)
+}
+
+/// The input of `day` for `seed`, of the generator's default size unless
+/// one is given.
+pub fn generate(day: u8, size: Option<usize>, seed: u64) -> Result<Generated, String> {
+    let generator = find(day).ok_or_else(|| format!("day {} has no generator", day))?;
+    Ok((generator.generate)(
+        size.unwrap_or(generator.size),
+        &mut Rng::new(seed),
+    ))
+}
+
+/// `size` lines mixing digits and spelled out digits between letters that
+/// spell none of them. Every line writes at least one digit as a digit, so
+/// part one finds a value on each.
+pub fn calibration_document(size: usize, rng: &mut Rng) -> Generated {
+    const WORDS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    const FILLER: &[u8] = b"abcdjklmpqyz";
+    let filler = |input: &mut String, rng: &mut Rng| {
+        for _ in 0..rng.range(0, 4) {
+            input.push(*rng.pick(FILLER) as char);
+        }
+    };
+
+    let mut input = String::new();
+    let (mut part_one, mut part_two) = (0, 0);
+    for _ in 0..size.max(1) {
+        let count = rng.range(1, 6) as usize;
+        let written = rng.range(0, count as u64) as usize;
+        // Each digit and whether it is written as a digit.
+        let digits: Vec<(u64, bool)> = (0..count)
+            .map(|i| (rng.range(1, 10), i == written || rng.percent(50)))
+            .collect();
+        for &(digit, as_digit) in &digits {
+            filler(&mut input, rng);
+            if as_digit {
+                input.push_str(&digit.to_string());
+            } else {
+                input.push_str(WORDS[digit as usize - 1]);
+            }
+        }
+        filler(&mut input, rng);
+        input.push('\n');
+
+        let written: Vec<u64> = digits
+            .iter()
+            .filter(|&&(_, as_digit)| as_digit)
+            .map(|&(digit, _)| digit)
+            .collect();
+        part_one += written[0] * 10 + written[written.len() - 1];
+        part_two += digits[0].0 * 10 + digits[count - 1].0;
+    }
+
+    Generated {
+        input,
+        part_one: Some(part_one.to_string()),
+        part_two: Some(part_two.to_string()),
+    }
+}
+
+/// `size` games of up to six rounds, each showing every color at least once.
+/// Now and then a round shows more cubes than the bag of part one holds.
+pub fn cube_games(size: usize, rng: &mut Rng) -> Generated {
+    const COLORS: [&str; 3] = ["red", "green", "blue"];
+    const BAG: [u64; 3] = [12, 13, 14];
+
+    let mut input = String::new();
+    let (mut part_one, mut part_two) = (0, 0);
+    for id in 1..=size.max(1) {
+        let mut rounds: Vec<Vec<(usize, u64)>> = (0..rng.range(1, 7))
+            .map(|_| {
+                let mut colors = [0, 1, 2];
+                rng.shuffle(&mut colors);
+                let shown = rng.range(1, 4) as usize;
+                colors[..shown]
+                    .iter()
+                    .map(|&color| {
+                        let high = if rng.percent(5) { 21 } else { 12 };
+                        (color, rng.range(1, high))
+                    })
+                    .collect()
+            })
+            .collect();
+        for color in 0..COLORS.len() {
+            if !rounds.iter().flatten().any(|&(shown, _)| shown == color) {
+                let round = rng.range(0, rounds.len() as u64) as usize;
+                rounds[round].push((color, rng.range(1, 12)));
+            }
+        }
+
+        let most = |color: usize| {
+            rounds
+                .iter()
+                .flatten()
+                .filter(|&&(shown, _)| shown == color)
+                .map(|&(_, count)| count)
+                .max()
+                .unwrap()
+        };
+        if (0..COLORS.len()).all(|color| most(color) <= BAG[color]) {
+            part_one += id;
+        }
+        part_two += (0..COLORS.len()).map(most).product::<u64>();
+
+        let rounds: Vec<String> = rounds
+            .iter()
+            .map(|round| {
+                round
+                    .iter()
+                    .map(|&(color, count)| format!("{} {}", count, COLORS[color]))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect();
+        input.push_str(&format!("Game {}: {}\n", id, rounds.join("; ")));
+    }
+
+    Generated {
+        input,
+        part_one: Some(part_one.to_string()),
+        part_two: Some(part_two.to_string()),
+    }
+}
+
+/// A `size` by `size` engine schematic of numbers up to three digits, apart
+/// from each other on their row, with symbols scattered between them.
+pub fn engine_schematic(size: usize, rng: &mut Rng) -> Generated {
+    const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+    let size = size.max(1);
+    let input = (0..size)
+        .map(|_| {
+            let mut row = vec![b'.'; size];
+            let mut x = 0;
+            while x < size {
+                if rng.percent(15) {
+                    let length = (rng.range(1, 4) as usize).min(size - x);
+                    row[x] = b'0' + rng.range(1, 10) as u8;
+                    for digit in &mut row[x + 1..x + length] {
+                        *digit = b'0' + rng.range(0, 10) as u8;
+                    }
+                    // Leave a gap, so the next number is a number of its own.
+                    x += length + 1;
+                    continue;
+                }
+                if rng.percent(10) {
+                    row[x] = *rng.pick(SYMBOLS);
+                }
+                x += 1;
+            }
+            String::from_utf8(row).unwrap() + "\n"
+        })
+        .collect();
+    Generated {
+        input,
+        ..Generated::default()
+    }
+}
+
+/// `size` cards of ten winning numbers and twenty-five numbers you have, all
+/// below 100. No card wins copies of cards past the end of the table, and
+/// cards with more than ten thousand copies win nothing, so the pile stays
+/// countable.
+pub fn scratchcards(size: usize, rng: &mut Rng) -> Generated {
+    let size = size.max(1);
+    let mut numbers: Vec<u64> = (1..100).collect();
+    let mut copies = vec![1u64; size];
+    let mut input = String::new();
+    let mut part_one = 0;
+    for index in 0..size {
+        let matches = if copies[index] > 10_000 {
+            0
+        } else {
+            (rng.range(0, 11) as usize).min(size - index - 1)
+        };
+        for won in index + 1..=index + matches {
+            copies[won] += copies[index];
+        }
+        if matches > 0 {
+            part_one += 1 << (matches - 1);
+        }
+
+        // The last `matches` winning numbers are the first numbers you have.
+        rng.shuffle(&mut numbers);
+        let mut have = numbers[10 - matches..35 - matches].to_vec();
+        rng.shuffle(&mut have);
+        let side = |numbers: &[u64]| {
+            numbers
+                .iter()
+                .map(|number| format!("{:>2}", number))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        input.push_str(&format!(
+            "Card {:>3}: {} | {}\n",
+            index + 1,
+            side(&numbers[..10]),
+            side(&have)
+        ));
+    }
+
+    Generated {
+        input,
+        part_one: Some(part_one.to_string()),
+        part_two: Some(copies.iter().sum::<u64>().to_string()),
+    }
+}
+
+/// An almanac with `size` ranges in every map. The source ranges of a map
+/// never overlap and neither do their destinations, and ten seed ranges make
+/// up the seeds line.
+pub fn almanac(size: usize, rng: &mut Rng) -> Generated {
+    const LIMIT: u64 = 1 << 32;
+    const CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    let size = size.max(1);
+
+    let seeds: Vec<u64> = (0..10)
+        .flat_map(|_| {
+            let length = rng.range(1, 1 << 28);
+            [rng.range(0, LIMIT - length), length]
+        })
+        .collect();
+    let mut input = format!(
+        "seeds: {}\n",
+        seeds
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+
+    let mut values = seeds.clone();
+    for categories in CATEGORIES.windows(2) {
+        let mut cuts: Vec<u64> = (0..size).map(|_| rng.range(0, LIMIT)).collect();
+        cuts.push(0);
+        cuts.push(LIMIT);
+        cuts.sort_unstable();
+        cuts.dedup();
+        // Most segments are mapped, the rest keep their numbers.
+        let mut sources: Vec<(u64, u64)> = cuts
+            .windows(2)
+            .map(|cut| (cut[0], cut[1] - cut[0]))
+            .filter(|_| rng.percent(75))
+            .collect();
+        let total: u64 = sources.iter().map(|&(_, length)| length).sum();
+        let mut destination = rng.range(0, LIMIT - total + 1);
+        rng.shuffle(&mut sources);
+        let mut entries: Vec<(u64, u64, u64)> = sources
+            .into_iter()
+            .map(|(source, length)| {
+                let entry = (destination, source, length);
+                destination += length;
+                entry
+            })
+            .collect();
+        rng.shuffle(&mut entries);
+
+        for value in values.iter_mut() {
+            if let Some(&(destination, source, _)) = entries
+                .iter()
+                .find(|&&(_, source, length)| (source..source + length).contains(value))
+            {
+                *value = *value - source + destination;
+            }
+        }
+        input.push_str(&format!("\n{}-to-{} map:\n", categories[0], categories[1]));
+        for (destination, source, length) in entries {
+            input.push_str(&format!("{} {} {}\n", destination, source, length));
+        }
+    }
+
+    Generated {
+        input,
+        part_one: values.iter().min().map(u64::to_string),
+        part_two: None,
+    }
+}
+
+/// `size` races of up to a hundred milliseconds. Each record is the distance
+/// of the hold just short of the first one that wins, which gives away the
+/// number of ways to win. Part two joins the columns into a race of its own,
+/// which is left to the solution.
+pub fn races(size: usize, rng: &mut Rng) -> Generated {
+    let size = size.max(1);
+    let mut ways: u128 = 1;
+    let (mut times, mut records) = (String::from("Time:    "), String::from("Distance:"));
+    for _ in 0..size {
+        let time = rng.range(7, 100);
+        let first = rng.range(1, time / 2 + 1);
+        times.push_str(&format!(" {:>6}", time));
+        records.push_str(&format!(" {:>6}", (first - 1) * (time - first + 1)));
+        ways *= (time - 2 * first + 1) as u128;
+    }
+    Generated {
+        input: format!("{}\n{}\n", times, records),
+        part_one: Some(ways.to_string()),
+        part_two: None,
+    }
+}
+
+/// `size` different hands with bids up to a thousand. The type of each hand
+/// is picked before its cards, which gives part one away. What the jokers
+/// of part two become is left to the solution.
+///
+/// Sizes are capped at 10000, well below the number of different hands.
+pub fn camel_cards(size: usize, rng: &mut Rng) -> Generated {
+    const LABELS: &[u8] = b"23456789TJQKA";
+    // The cards of each label in a hand, from the weakest type to the
+    // strongest.
+    const TYPES: [&[usize]; 7] = [
+        &[1, 1, 1, 1, 1],
+        &[2, 1, 1, 1],
+        &[2, 2, 1],
+        &[3, 1, 1],
+        &[3, 2],
+        &[4, 1],
+        &[5],
+    ];
+    let size = size.clamp(1, 10_000);
+
+    let mut used = HashSet::new();
+    let mut hands = Vec::new();
+    while hands.len() < size {
+        let kind = rng.range(0, TYPES.len() as u64) as usize;
+        let mut labels: Vec<usize> = (0..LABELS.len()).collect();
+        rng.shuffle(&mut labels);
+        let mut cards: Vec<usize> = TYPES[kind]
+            .iter()
+            .zip(labels)
+            .flat_map(|(&count, label)| vec![label; count])
+            .collect();
+        rng.shuffle(&mut cards);
+        if used.insert(cards.clone()) {
+            hands.push((kind, cards, rng.range(1, 1001)));
+        }
+    }
+
+    let input = hands
+        .iter()
+        .map(|(_, cards, bid)| {
+            let cards: String = cards.iter().map(|&card| LABELS[card] as char).collect();
+            format!("{} {}\n", cards, bid)
+        })
+        .collect();
+    hands.sort_unstable();
+    let winnings: u64 = hands
+        .iter()
+        .enumerate()
+        .map(|(rank, (_, _, bid))| (rank as u64 + 1) * bid)
+        .sum();
+    Generated {
+        input,
+        part_one: Some(winnings.to_string()),
+        part_two: None,
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// A network of three to six ghosts, each on a cycle of `size` times a
+/// distinct prime steps that passes its `..Z` node once, at the end. The
+/// number of ghosts and their primes, out of those up to 17, are drawn from
+/// `rng`, the first ghost's prime giving part one. The instructions are
+/// `size` long. Most nodes lead to the same node either way, the others
+/// split into two that meet again on the next step, so the instructions
+/// pick the nodes visited but not the length of the cycles.
+///
+/// Sizes are capped at 500, beyond which three characters do not name every
+/// node.
+pub fn network(size: usize, rng: &mut Rng) -> Generated {
+    const PRIMES: [u64; 7] = [2, 3, 5, 7, 11, 13, 17];
+    const CHARACTERS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let size = size.clamp(1, 500);
+
+    let instructions: String = (0..size)
+        .map(|_| if rng.percent(50) { 'L' } else { 'R' })
+        .collect();
+    let mut used = HashSet::new();
+    let mut name = |rng: &mut Rng, last: u8| loop {
+        let name =
+            String::from_utf8(vec![*rng.pick(CHARACTERS), *rng.pick(CHARACTERS), last]).unwrap();
+        if !["AAA", "ZZZ"].contains(&name.as_str()) && used.insert(name.clone()) {
+            break name;
+        }
+    };
+    let inner = |rng: &mut Rng| loop {
+        let last = *rng.pick(CHARACTERS);
+        if last != b'A' && last != b'Z' {
+            break last;
+        }
+    };
+
+    let mut primes = PRIMES;
+    rng.shuffle(&mut primes);
+    let ghosts = rng.range(3, 7) as usize;
+
+    let mut nodes: Vec<(String, String, String)> = Vec::new();
+    let mut lengths = Vec::new();
+    for (ghost, prime) in primes[..ghosts].iter().enumerate() {
+        let length = size as u64 * prime;
+        let (start, end) = if ghost == 0 {
+            ("AAA".to_string(), "ZZZ".to_string())
+        } else {
+            (name(rng, b'A'), name(rng, b'Z'))
+        };
+        // The nodes reached after each step, the last one being the end.
+        let path: Vec<String> = (1..=length)
+            .map(|step| {
+                if step == length {
+                    end.clone()
+                } else {
+                    let last = inner(rng);
+                    name(rng, last)
+                }
+            })
+            .collect();
+        // Both the start and the end lead to the first node of the cycle.
+        let mut from = vec![start, end];
+        for (step, to) in path.iter().enumerate() {
+            let diamond = step + 1 < path.len() && from.len() == 1 && rng.percent(25);
+            if diamond {
+                // Replace this node by two alternatives leading to the next.
+                let last = inner(rng);
+                let alternative = name(rng, last);
+                let (left, right) = if rng.percent(50) {
+                    (to.clone(), alternative.clone())
+                } else {
+                    (alternative.clone(), to.clone())
+                };
+                nodes.push((from[0].clone(), left, right));
+                from = vec![to.clone(), alternative];
+                continue;
+            }
+            for node in &from {
+                nodes.push((node.clone(), to.clone(), to.clone()));
+            }
+            from = vec![to.clone()];
+        }
+        lengths.push(length);
+    }
+    rng.shuffle(&mut nodes);
+
+    let mut input = format!("{}\n\n", instructions);
+    for (node, left, right) in &nodes {
+        input.push_str(&format!("{} = ({}, {})\n", node, left, right));
+    }
+    let lcm = lengths
+        .iter()
+        .fold(1, |lcm, &length| lcm / gcd(lcm, length) * length);
+    Generated {
+        input,
+        part_one: Some(lengths[0].to_string()),
+        part_two: Some(lcm.to_string()),
+    }
+}
+
+/// `n` choose `k`, for any `n` including negative ones.
+fn binomial(n: i64, k: i64) -> i64 {
+    (0..k).fold(1, |binomial, i| binomial * (n - i) / (i + 1))
+}
+
+/// `size` histories of twenty-one values, the values at 0 to 20 of a
+/// polynomial of degree up to five. Written as a sum of binomial
+/// coefficients, the polynomial gives away its values at 21 and -1.
+pub fn oasis_report(size: usize, rng: &mut Rng) -> Generated {
+    let mut input = String::new();
+    let (mut part_one, mut part_two) = (0, 0);
+    for _ in 0..size.max(1) {
+        let coefficients: Vec<i64> = (0..=rng.range(0, 6))
+            .map(|_| rng.range(0, 41) as i64 - 20)
+            .collect();
+        let value = |x: i64| {
+            (0..)
+                .zip(&coefficients)
+                .map(|(k, coefficient)| coefficient * binomial(x, k))
+                .sum::<i64>()
+        };
+        let history: Vec<String> = (0..21).map(|x| value(x).to_string()).collect();
+        input.push_str(&history.join(" "));
+        input.push('\n');
+        part_one += value(21);
+        part_two += value(-1);
+    }
+    Generated {
+        input,
+        part_one: Some(part_one.to_string()),
+        part_two: Some(part_two.to_string()),
+    }
+}
+
+/// A region of whole blocks, one run of rows per column, where neighbouring
+/// columns share at least one row. Its outline never touches itself.
+fn columns(width: usize, height: usize, rng: &mut Rng) -> Vec<(u64, u64)> {
+    let height = height as u64;
+    let top = rng.range(0, height);
+    let mut columns = vec![(top, rng.range(top + 1, height + 1))];
+    while columns.len() < width {
+        let (top, bottom) = *columns.last().unwrap();
+        let next_top = rng.range(0, bottom);
+        let next_bottom = rng.range(top.max(next_top) + 1, height + 1);
+        columns.push((next_top, next_bottom));
+    }
+    columns
+}
+
+/// The corners of the outline of `columns`, clockwise from the top left,
+/// with the columns `widths` wide and the rows `heights` high.
+fn outline(columns: &[(u64, u64)], widths: &[i64], heights: &[i64]) -> Vec<(i64, i64)> {
+    let x = |column: usize| widths[..column].iter().sum::<i64>();
+    let y = |row: u64| heights[..row as usize].iter().sum::<i64>();
+    let mut corners = Vec::new();
+    for (column, &(top, _)) in columns.iter().enumerate() {
+        corners.push((x(column), y(top)));
+        corners.push((x(column + 1), y(top)));
+    }
+    for (column, &(_, bottom)) in columns.iter().enumerate().rev() {
+        corners.push((x(column + 1), y(bottom)));
+        corners.push((x(column), y(bottom)));
+    }
+    corners.dedup();
+    if corners.first() == corners.last() {
+        corners.pop();
+    }
+    // Drop corners in the middle of a straight edge.
+    let count = corners.len();
+    (0..count)
+        .filter(|&i| {
+            let (previous, corner, next) = (
+                corners[(i + count - 1) % count],
+                corners[i],
+                corners[(i + 1) % count],
+            );
+            !((previous.0 == corner.0 && corner.0 == next.0)
+                || (previous.1 == corner.1 && corner.1 == next.1))
+        })
+        .map(|i| corners[i])
+        .collect()
+}
+
+/// The blocks on the outline and inside it, by the shoelace formula and
+/// Pick's theorem.
+fn lagoon(corners: &[(i64, i64)]) -> (i64, i64) {
+    let (mut area, mut boundary) = (0, 0);
+    for (i, &(x, y)) in corners.iter().enumerate() {
+        let (next_x, next_y) = corners[(i + 1) % corners.len()];
+        area += x * next_y - next_x * y;
+        boundary += (next_x - x).abs() + (next_y - y).abs();
+    }
+    let inside = area.abs() / 2 - boundary / 2 + 1;
+    (boundary, inside)
+}
+
+/// Every block along the outline, in order.
+fn trace(corners: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut blocks = Vec::new();
+    for (i, &(x, y)) in corners.iter().enumerate() {
+        let (next_x, next_y) = corners[(i + 1) % corners.len()];
+        let (dx, dy) = ((next_x - x).signum(), (next_y - y).signum());
+        let (mut x, mut y) = (x, y);
+        while (x, y) != (next_x, next_y) {
+            blocks.push((x, y));
+            x += dx;
+            y += dy;
+        }
+    }
+    blocks
+}
+
+/// A `size` by `size` field with a single loop through its tiles and other
+/// pipes scattered around it, including inside the loop. Nothing but the
+/// loop connects to the start.
+pub fn pipe_loop(size: usize, rng: &mut Rng) -> Generated {
+    let size = size.max(4);
+    let cells = size - 3;
+    let columns = columns(cells, cells, rng);
+    let corners = outline(&columns, &vec![1; cells], &vec![1; cells]);
+    let (boundary, inside) = lagoon(&corners);
+    let blocks = trace(&corners);
+
+    const JUNK: [u8; 10] = [b'.', b'.', b'.', b'.', b'|', b'-', b'L', b'J', b'7', b'F'];
+    let mut grid: Vec<Vec<u8>> = (0..size)
+        .map(|_| (0..size).map(|_| *rng.pick(&JUNK)).collect())
+        .collect();
+    let tile = |x: i64, y: i64| ((x + 1) as usize, (y + 1) as usize);
+    for (i, &(x, y)) in blocks.iter().enumerate() {
+        let previous = blocks[(i + blocks.len() - 1) % blocks.len()];
+        let next = blocks[(i + 1) % blocks.len()];
+        let towards = |(other_x, other_y): (i64, i64)| (other_x - x, other_y - y);
+        let mut ends = [towards(previous), towards(next)];
+        ends.sort_unstable();
+        let pipe = match ends {
+            [(0, -1), (0, 1)] => b'|',
+            [(-1, 0), (1, 0)] => b'-',
+            [(0, -1), (1, 0)] => b'L',
+            [(-1, 0), (0, -1)] => b'J',
+            [(-1, 0), (0, 1)] => b'7',
+            [(0, 1), (1, 0)] => b'F',
+            _ => unreachable!("{:?}", ends),
+        };
+        let (column, row) = tile(x, y);
+        grid[row][column] = pipe;
+    }
+
+    let start = rng.range(0, blocks.len() as u64) as usize;
+    let (x, y) = blocks[start];
+    let neighbours = [
+        blocks[(start + blocks.len() - 1) % blocks.len()],
+        blocks[(start + 1) % blocks.len()],
+    ];
+    let on_loop: HashSet<&(i64, i64)> = blocks.iter().collect();
+    for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+        if !neighbours.contains(&(x + dx, y + dy)) && !on_loop.contains(&(x + dx, y + dy)) {
+            let (column, row) = tile(x + dx, y + dy);
+            grid[row][column] = b'.';
+        }
+    }
+    let (column, row) = tile(x, y);
+    grid[row][column] = b'S';
+
+    let input = grid
+        .into_iter()
+        .map(|row| String::from_utf8(row).unwrap() + "\n")
+        .collect();
+    Generated {
+        input,
+        part_one: Some((boundary / 2).to_string()),
+        part_two: Some(inside.to_string()),
+    }
+}
+
+/// `size` steps on a single line, over a quarter as many labels so that
+/// lenses get replaced and removed again. The hashes of the steps give part
+/// one away, where the lenses end up is left to the solution.
+pub fn initialization_sequence(size: usize, rng: &mut Rng) -> Generated {
+    let size = size.max(1);
+    let labels: Vec<String> = (0..size.div_ceil(4))
+        .map(|_| {
+            (0..rng.range(2, 7))
+                .map(|_| (b'a' + rng.range(0, 26) as u8) as char)
+                .collect()
+        })
+        .collect();
+    let steps: Vec<String> = (0..size)
+        .map(|_| {
+            let label = rng.pick(&labels);
+            if rng.percent(60) {
+                format!("{}={}", label, rng.range(1, 10))
+            } else {
+                format!("{}-", label)
+            }
+        })
+        .collect();
+    let hashes: u64 = steps
+        .iter()
+        .map(|step| {
+            step.bytes()
+                .fold(0, |hash, byte| (hash + byte as u64) * 17 % 256)
+        })
+        .sum();
+    Generated {
+        input: steps.join(",") + "\n",
+        part_one: Some(hashes.to_string()),
+        part_two: None,
+    }
+}
+
+/// A `size` by `size` contraption where about one tile in ten is a mirror or
+/// a splitter.
+pub fn contraption(size: usize, rng: &mut Rng) -> Generated {
+    const DEVICES: [char; 4] = ['/', '\\', '|', '-'];
+    let size = size.max(1);
+    let input = (0..size)
+        .map(|_| {
+            let row: String = (0..size)
+                .map(|_| {
+                    if rng.percent(10) {
+                        *rng.pick(&DEVICES)
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            row + "\n"
+        })
+        .collect();
+    Generated {
+        input,
+        ..Generated::default()
+    }
+}
+
+/// A `size` by `size` map of heat loss between 1 and 9, lower towards the
+/// diagonal from the top left to the bottom right, like the real maps.
+pub fn heat_map(size: usize, rng: &mut Rng) -> Generated {
+    let size = size.max(1);
+    let input = (0..size)
+        .map(|y| {
+            let row: String = (0..size)
+                .map(|x| {
+                    let distance = (x as i64 - y as i64).unsigned_abs() * 6 / size as u64;
+                    let loss = (1 + distance + rng.range(0, 4)).min(9);
+                    char::from_digit(loss as u32, 10).unwrap()
+                })
+                .collect();
+            row + "\n"
+        })
+        .collect();
+    Generated {
+        input,
+        ..Generated::default()
+    }
+}
+
+/// A closed dig plan around `size` columns of a few metres each, which never
+/// crosses itself. The colors encode a plan of the same shape with distances
+/// in the tens of thousands, as part two reads them.
+///
+/// Sizes are capped at 100, beyond which the plan leaves the grid of part
+/// one, which reaches 500 metres from the start.
+pub fn dig_plan(size: usize, rng: &mut Rng) -> Generated {
+    let size = size.clamp(1, 100);
+    let columns = columns(size, size, rng);
+    let small: Vec<i64> = (0..2 * size).map(|_| rng.range(1, 5) as i64).collect();
+    let large: Vec<i64> = (0..2 * size)
+        .map(|_| rng.range(1, 0xfffff / size as u64) as i64)
+        .collect();
+    let part_one = outline(&columns, &small[..size], &small[size..]);
+    let part_two = outline(&columns, &large[..size], &large[size..]);
+
+    let steps = |corners: &[(i64, i64)]| -> Vec<(u8, i64)> {
+        (0..corners.len())
+            .map(|i| {
+                let (x, y) = corners[i];
+                let (next_x, next_y) = corners[(i + 1) % corners.len()];
+                let direction = match ((next_x - x).signum(), (next_y - y).signum()) {
+                    (1, 0) => 0,
+                    (0, 1) => 1,
+                    (-1, 0) => 2,
+                    _ => 3,
+                };
+                (direction, (next_x - x).abs() + (next_y - y).abs())
+            })
+            .collect()
+    };
+    let input = steps(&part_one)
+        .into_iter()
+        .zip(steps(&part_two))
+        .map(|((direction, distance), (encoded, length))| {
+            let direction = ["R", "D", "L", "U"][direction as usize];
+            format!("{} {} (#{:05x}{})\n", direction, distance, length, encoded)
+        })
+        .collect();
+    let area = |corners: &[(i64, i64)]| {
+        let (boundary, inside) = lagoon(corners);
+        (boundary + inside).to_string()
+    };
+    Generated {
+        input,
+        part_one: Some(area(&part_one)),
+        part_two: Some(area(&part_two)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{days, verify};
+
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        for generator in GENERATORS {
+            let first = generate(generator.day, Some(8), 1).unwrap();
+            assert_eq!(first, generate(generator.day, Some(8), 1).unwrap());
+            assert_ne!(first, generate(generator.day, Some(8), 2).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_sizes_are_capped() {
+        let generated = generate(18, Some(1 << 20), 1).unwrap();
+        assert_eq!(generated, generate(18, Some(100), 1).unwrap());
+    }
+
+    #[test]
+    fn test_network_answers_vary() {
+        let answers = |seed| {
+            let generated = generate(8, None, seed).unwrap();
+            (generated.part_one, generated.part_two)
+        };
+        assert_ne!(answers(1), answers(2));
+        let distinct: HashSet<_> = (0..8).map(answers).collect();
+        assert!(distinct.len() > 4, "{:?}", distinct);
+    }
+
+    #[test]
+    fn test_outline() {
+        let columns = [(1, 3), (0, 2), (1, 2)];
+        let corners = outline(&columns, &[1, 1, 1], &[1, 1, 1]);
+        assert_eq!(
+            corners,
+            [
+                (0, 1),
+                (1, 1),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (3, 1),
+                (3, 2),
+                (1, 2),
+                (1, 3),
+                (0, 3)
+            ]
+        );
+        // An area of five with every corner of its blocks on the outline.
+        assert_eq!(lagoon(&corners), (12, 0));
+    }
+
+    /// The solutions agree with the answers the generators know, and take
+    /// whatever else they generate.
+    #[test]
+    fn test_solutions() {
+        for generator in GENERATORS {
+            let day = days::find(generator.day).unwrap();
+            for seed in 0..3 {
+                let generated = generate(generator.day, Some(12), seed).unwrap();
+                for (part, solve) in day.parts() {
+                    let (found, _) = verify::run(solve, &generated.input);
+                    let found = found.map(|answer| answer.value);
+                    assert!(
+                        found.is_ok(),
+                        "day {} part {} seed {}: {:?}\n{}",
+                        day.day,
+                        part,
+                        seed,
+                        found,
+                        generated.input
+                    );
+                    if let Some(answer) = generated.answer(part) {
+                        assert_eq!(
+                            found.unwrap(),
+                            answer,
+                            "day {} part {} seed {}\n{}",
+                            day.day,
+                            part,
+                            seed,
+                            generated.input
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    cell::Cell,
+    env, thread,
+    time::{Duration, Instant},
+};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const USER_AGENT: &str = "github.com/danielmulvad/adventofcode_2023 runner";
+/// The least time left between two requests unless configured otherwise.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// Sent as `application/x-www-form-urlencoded` when not empty.
+    pub form: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Whatever actually talks to the server, so it can be swapped out.
+pub trait Backend {
+    fn send(&self, request: &Request) -> Result<Response, String>;
+}
+
+/// The default backend. Error statuses are returned as responses, only
+/// transport failures are errors.
+#[derive(Clone, Debug)]
+pub struct Ureq {
+    agent: ureq::Agent,
+}
+
+impl Default for Ureq {
+    fn default() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+}
+
+impl Backend for Ureq {
+    fn send(&self, request: &Request) -> Result<Response, String> {
+        let method = match request.method {
+            Method::Get => "GET",
+            Method::Post => "POST",
+        };
+        let mut call = self.agent.request(method, &request.url);
+        for (name, value) in &request.headers {
+            call = call.set(name, value);
+        }
+        let result = if request.form.is_empty() {
+            call.call()
+        } else {
+            let form: Vec<(&str, &str)> = request
+                .form
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect();
+            call.send_form(&form)
+        };
+        let response = match result {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(format!("{} {}: {}", method, request.url, e)),
+        };
+        let status = response.status();
+        let body = response
+            .into_string()
+            .map_err(|e| format!("{} {}: {}", method, request.url, e))?;
+        Ok(Response { status, body })
+    }
+}
+
+/// An authenticated, rate limited client for the puzzle site.
+pub struct Client<B: Backend = Ureq> {
+    backend: B,
+    base_url: String,
+    session: String,
+    user_agent: String,
+    min_interval: Duration,
+    last_request: Cell<Option<Instant>>,
+}
+
+impl Client {
+    pub fn new(session: impl Into<String>) -> Self {
+        Self::with_backend(Ureq::default(), session)
+    }
+
+    /// Configures the client from the environment, which `setup` fills from
+    /// `.env`: `AOC_SESSION` is required, `AOC_BASE_URL`, `AOC_USER_AGENT`
+    /// and `AOC_MIN_INTERVAL_MS` are optional.
+    pub fn from_env() -> Result<Self, String> {
+        let session = env::var("AOC_SESSION")
+            .map_err(|_| "AOC_SESSION is not set, add the session cookie to .env".to_string())?;
+        let mut client = Self::new(session);
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            client = client.with_base_url(base_url);
+        }
+        if let Ok(user_agent) = env::var("AOC_USER_AGENT") {
+            client = client.with_user_agent(user_agent);
+        }
+        if let Ok(milliseconds) = env::var("AOC_MIN_INTERVAL_MS") {
+            let milliseconds = milliseconds
+                .parse::<u64>()
+                .map_err(|_| format!("AOC_MIN_INTERVAL_MS {:?} is not a number", milliseconds))?;
+            client = client.with_min_interval(Duration::from_millis(milliseconds));
+        }
+        Ok(client)
+    }
+}
+
+impl<B: Backend> Client<B> {
+    pub fn with_backend(backend: B, session: impl Into<String>) -> Self {
+        Self {
+            backend,
+            base_url: BASE_URL.to_string(),
+            session: session.into(),
+            user_agent: USER_AGENT.to_string(),
+            min_interval: MIN_INTERVAL,
+            last_request: Cell::new(None),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn get(&self, path: &str) -> Result<Response, String> {
+        self.send(Method::Get, path, Vec::new())
+    }
+
+    pub fn post_form(&self, path: &str, form: Vec<(String, String)>) -> Result<Response, String> {
+        self.send(Method::Post, path, form)
+    }
+
+    fn send(
+        &self,
+        method: Method,
+        path: &str,
+        form: Vec<(String, String)>,
+    ) -> Result<Response, String> {
+        if let Some(last_request) = self.last_request.get() {
+            let elapsed = last_request.elapsed();
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+        let request = Request {
+            method,
+            url: format!("{}{}", self.base_url, path),
+            headers: vec![
+                ("Cookie".to_string(), format!("session={}", self.session)),
+                ("User-Agent".to_string(), self.user_agent.clone()),
+            ],
+            form,
+        };
+        let response = self.backend.send(&request);
+        self.last_request.set(Some(Instant::now()));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::MockServer;
+
+    use super::*;
+
+    #[test]
+    fn test_headers_and_form() {
+        let server = MockServer::start(|request| {
+            let status = if request.url == "/2023/day/1/answer" {
+                200
+            } else {
+                404
+            };
+            (status, request.body.clone())
+        });
+        let client = Client::new("abc")
+            .with_base_url(server.url())
+            .with_user_agent("tests")
+            .with_min_interval(Duration::ZERO);
+
+        let response = client
+            .post_form(
+                "/2023/day/1/answer",
+                vec![
+                    ("level".to_string(), "1".to_string()),
+                    ("answer".to_string(), "42".to_string()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "level=1&answer=42");
+
+        let response = client.get("/missing").unwrap();
+        assert_eq!(response.status, 404);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].header("Cookie"), Some("session=abc"));
+        assert_eq!(requests[0].header("User-Agent"), Some("tests"));
+        assert_eq!(requests[1].method, "GET");
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let server = MockServer::start(|_| (200, String::new()));
+        let client = Client::new("abc")
+            .with_base_url(server.url())
+            .with_min_interval(Duration::from_millis(200));
+        let start = Instant::now();
+        client.get("/").unwrap();
+        client.get("/").unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
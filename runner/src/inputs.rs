@@ -0,0 +1,158 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::http::{Backend, Client, Ureq};
+
+pub const YEAR: u16 = 2023;
+
+/// Puzzle inputs cached under a data directory as `<year>/day_<day>.txt`,
+/// fetched from the site the first time they are asked for.
+pub struct Inputs<B: Backend = Ureq> {
+    year: u16,
+    data_dir: PathBuf,
+    client: Option<Client<B>>,
+}
+
+impl Inputs {
+    /// Configures the inputs from the environment: `AOC_YEAR` and
+    /// `AOC_DATA_DIR` pick the year and the cache, fetching is only possible
+    /// when `AOC_SESSION` is set (see [`Client::from_env`]).
+    pub fn from_env() -> Result<Self, String> {
+        let year = match env::var("AOC_YEAR") {
+            Ok(year) => year
+                .parse::<u16>()
+                .map_err(|_| format!("AOC_YEAR {:?} is not a year", year))?,
+            Err(_) => YEAR,
+        };
+        let data_dir = env::var_os("AOC_DATA_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| crate::root().join("data"));
+        let mut inputs = Self::new(year, data_dir);
+        if env::var_os("AOC_SESSION").is_some() {
+            inputs = inputs.with_client(Client::from_env()?);
+        }
+        Ok(inputs)
+    }
+}
+
+impl<B: Backend> Inputs<B> {
+    pub fn new(year: u16, data_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            year,
+            data_dir: data_dir.into(),
+            client: None,
+        }
+    }
+
+    pub fn with_client<C: Backend>(self, client: Client<C>) -> Inputs<C> {
+        Inputs {
+            year: self.year,
+            data_dir: self.data_dir,
+            client: Some(client),
+        }
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn client(&self) -> Option<&Client<B>> {
+        self.client.as_ref()
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Where the input of `day` is cached, whether it is there or not.
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.data_dir
+            .join(self.year.to_string())
+            .join(format!("day_{}.txt", day))
+    }
+
+    /// The cached input of `day`, fetching it on a miss.
+    pub fn resolve(&self, day: u8) -> Result<PathBuf, String> {
+        let path = self.path(day);
+        if path.is_file() {
+            return Ok(path);
+        }
+        let client = self.client.as_ref().ok_or_else(|| {
+            format!(
+                "{} is not cached and AOC_SESSION is not set to fetch it",
+                path.display()
+            )
+        })?;
+        let response = client.get(&format!("/{}/day/{}/input", self.year, day))?;
+        if response.status != 200 {
+            return Err(format!(
+                "fetching day {} of {} failed with status {}: {}",
+                day,
+                self.year,
+                response.status,
+                response.body.trim()
+            ));
+        }
+
+        let directory = path.parent().unwrap();
+        fs::create_dir_all(directory).map_err(|e| format!("{}: {}", directory.display(), e))?;
+        // Written aside first so an interrupted fetch never looks cached.
+        let partial = path.with_extension("txt.partial");
+        fs::write(&partial, &response.body).map_err(|e| format!("{}: {}", partial.display(), e))?;
+        fs::rename(&partial, &path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    pub fn read(&self, day: u8) -> Result<String, String> {
+        let path = self.resolve(day)?;
+        fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::mock::MockServer;
+
+    use super::*;
+
+    fn data_dir(name: &str) -> PathBuf {
+        let data_dir =
+            env::temp_dir().join(format!("runner-inputs-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&data_dir).ok();
+        data_dir
+    }
+
+    #[test]
+    fn test_fetch_then_cache() {
+        let server = MockServer::start(|request| match request.url.as_str() {
+            "/2023/day/3/input" => (200, "467..114..\n".to_string()),
+            _ => (404, "404 Not Found".to_string()),
+        });
+        let data_dir = data_dir("fetch");
+        let client = Client::new("abc")
+            .with_base_url(server.url())
+            .with_min_interval(Duration::ZERO);
+        let inputs = Inputs::<Ureq>::new(2023, &data_dir).with_client(client);
+
+        assert_eq!(inputs.read(3).unwrap(), "467..114..\n");
+        assert_eq!(inputs.read(3).unwrap(), "467..114..\n");
+        assert_eq!(server.requests().len(), 1);
+        assert!(data_dir.join("2023").join("day_3.txt").is_file());
+
+        let error = inputs.read(4).unwrap_err();
+        assert!(error.contains("status 404"), "{}", error);
+        assert!(!inputs.path(4).exists());
+        fs::remove_dir_all(data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_miss_without_session() {
+        let inputs = Inputs::<Ureq>::new(2023, data_dir("offline"));
+        let error = inputs.read(1).unwrap_err();
+        assert!(error.contains("AOC_SESSION"), "{}", error);
+    }
+}
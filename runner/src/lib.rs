@@ -0,0 +1,25 @@
+pub mod allocations;
+pub mod answers;
+pub mod days;
+pub mod examples;
+pub mod generate;
+pub mod http;
+pub mod inputs;
+pub mod memory;
+pub mod output;
+pub mod profile;
+pub mod scaffold;
+pub mod submit;
+pub mod verify;
+
+#[cfg(test)]
+mod mock;
+
+use std::{env, path::PathBuf};
+
+/// The repository holding the `day_N` crates, `AOC_ROOT` if set.
+pub fn root() -> PathBuf {
+    env::var_os("AOC_ROOT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/..")))
+}
@@ -0,0 +1,376 @@
+use std::{env, fs, io, panic, path::PathBuf, process::ExitCode};
+
+use day_4::{card::Card, explain};
+use dotenv::dotenv;
+use runner::allocations;
+use runner::days::{self, Day};
+use runner::examples::{self, Examples};
+use runner::generate;
+use runner::inputs::Inputs;
+use runner::output::{self, Format};
+use runner::profile::{Guard, Profile};
+use runner::scaffold;
+use runner::submit::{self, Outcome, Submitter};
+use runner::verify;
+use tracing::{error, info, warn};
+
+/// Installs logging and the exporters asked for on the command line. Logging
+/// is installed even when that fails, to report why.
+fn setup(args: &mut Vec<String>) -> Result<Guard, String> {
+    dotenv().ok();
+    profile(args)
+        .and_then(|profile| profile.init())
+        .inspect_err(|_| {
+            Profile::default().init().ok();
+        })
+}
+
+/// Takes `name <value>` out of `args`.
+fn take(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    let Some(index) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    args.remove(index);
+    if index == args.len() {
+        return Err(format!("{} needs a value", name));
+    }
+    Ok(Some(args.remove(index)))
+}
+
+/// Takes `--chrome-trace <file>` and `--folded <file>` out of `args`.
+fn profile(args: &mut Vec<String>) -> Result<Profile, String> {
+    let mut profile = Profile::default();
+    if let Some(path) = take(args, "--chrome-trace")? {
+        profile = profile.with_chrome_trace(path);
+    }
+    if let Some(path) = take(args, "--folded")? {
+        profile = profile.with_folded(path);
+    }
+    Ok(profile)
+}
+
+/// The days named on the command line, or all of them.
+fn selected(args: &[String]) -> Result<Vec<&'static Day>, String> {
+    if args.is_empty() {
+        return Ok(days::DAYS.iter().collect());
+    }
+    args.iter()
+        .map(|arg| {
+            let day = arg
+                .parse::<u8>()
+                .map_err(|_| format!("{:?} is not a day", arg))?;
+            days::find(day).ok_or_else(|| format!("day {} is not implemented", day))
+        })
+        .collect()
+}
+
+/// Parses the `--format <text|json|jsonl>` of the report, defaulting to text.
+fn report_format(format: Option<&str>) -> Result<Format, String> {
+    format.map_or(Ok(Format::Text), str::parse)
+}
+
+/// `submit <day> <part> [answer]`, solving the part when no answer is given.
+fn submit(inputs: &Inputs, args: &[String]) -> Result<Outcome, String> {
+    let (day, part, answer) = match args {
+        [day, part] => (day, part, None),
+        [day, part, answer] => (day, part, Some(answer.clone())),
+        _ => return Err("usage: runner submit <day> <part> [answer]".to_string()),
+    };
+    let day = selected(std::slice::from_ref(day))?[0];
+    let part = part
+        .parse::<u8>()
+        .map_err(|_| format!("{:?} is not a part", part))?;
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let (_, solve) = day
+                .parts()
+                .find(|&(solved, _)| solved == part)
+                .ok_or_else(|| format!("day {} part {} is not implemented", day.day, part))?;
+            let input = verify::input(&day.directory(runner::root()), inputs, day.day)?;
+            verify::run(solve, &input).0?.value
+        }
+    };
+    let client = inputs
+        .client()
+        .ok_or("AOC_SESSION is not set, add the session cookie to .env")?;
+    let history = submit::history_path(inputs.data_dir(), inputs.year());
+    info!("Submitting {} for day {} part {}", answer, day.day, part);
+    Submitter::new(client, inputs.year(), history).submit(day.day, part, &answer)
+}
+
+/// `explain 4`, printing the per-card breakdown of day 4 in the
+/// `--format <table|csv|json>` asked for, a table by default.
+fn explain(inputs: &Inputs, args: &[String], format: Option<&str>) -> Result<(), String> {
+    let [day] = args else {
+        return Err("usage: runner explain 4 [--format <table|csv|json>]".to_string());
+    };
+    let day = selected(std::slice::from_ref(day))?[0];
+    if day.day != 4 {
+        return Err(format!("day {} has no breakdown to explain", day.day));
+    }
+    let format = format.unwrap_or("table").parse::<explain::Format>()?;
+    let input = verify::input(&day.directory(runner::root()), inputs, day.day)?;
+    let cards = Card::parse_all(&input.lines().collect::<Vec<_>>())?;
+    explain::write(&explain::breakdown(&cards)?, format, io::stdout().lock())
+        .map_err(|e| e.to_string())
+}
+
+/// `generate <day> [size] [seed]`, printing the input and logging the
+/// answers the generator knows.
+fn generate(args: &[String]) -> Result<(), String> {
+    let (day, size, seed) = match args {
+        [day] => (day, None, None),
+        [day, size] => (day, Some(size), None),
+        [day, size, seed] => (day, Some(size), Some(seed)),
+        _ => return Err("usage: runner generate <day> [size] [seed]".to_string()),
+    };
+    let day = day
+        .parse::<u8>()
+        .map_err(|_| format!("{:?} is not a day", day))?;
+    let size = size
+        .map(|size| {
+            size.parse::<usize>()
+                .map_err(|_| format!("{:?} is not a size", size))
+        })
+        .transpose()?;
+    let seed = seed
+        .map(|seed| {
+            seed.parse::<u64>()
+                .map_err(|_| format!("{:?} is not a seed", seed))
+        })
+        .transpose()?
+        .unwrap_or(0);
+    let generated = generate::generate(day, size, seed)?;
+    print!("{}", generated.input);
+    for part in [1, 2] {
+        if let Some(answer) = generated.answer(part) {
+            info!("Day {} part {}: {}", day, part, answer);
+        }
+    }
+    Ok(())
+}
+
+/// `new-day <day> [puzzle.html]`, taking the statements from the saved puzzle
+/// page and the input from the cache when they are available.
+fn new_day(inputs: &Inputs, args: &[String]) -> Result<Vec<PathBuf>, String> {
+    let (day, html) = match args {
+        [day] => (day, None),
+        [day, html] => (day, Some(html)),
+        _ => return Err("usage: runner new-day <day> [puzzle.html]".to_string()),
+    };
+    let day = day
+        .parse::<u8>()
+        .map_err(|_| format!("{:?} is not a day", day))?;
+    let html = html
+        .map(|html| fs::read_to_string(html).map_err(|e| format!("{}: {}", html, e)))
+        .transpose()?;
+    let input = match inputs.read(day) {
+        Ok(input) => Some(input),
+        Err(e) => {
+            warn!("Leaving input.txt empty: {}", e);
+            None
+        }
+    };
+    scaffold::new_day(&runner::root(), day, html.as_deref(), input.as_deref())
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    // Dropping the guard at the end of `main` flushes the exporters.
+    let _guard = match setup(&mut args) {
+        Ok(guard) => guard,
+        Err(e) => {
+            error!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    // Parsed once the command is known, `explain` has formats of its own.
+    let format = match take(&mut args, "--format") {
+        Ok(format) => format,
+        Err(e) => {
+            error!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    // `--allocations` adds the heap use of every part to the report.
+    if let Some(index) = args.iter().position(|arg| arg == "--allocations") {
+        args.remove(index);
+        if let Err(e) = allocations::enable() {
+            error!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    // `--allow-missing` lets `verify` pass with unrecorded answers or inputs.
+    let allow_missing = match args.iter().position(|arg| arg == "--allow-missing") {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    };
+    let (command, args) = match args.split_first() {
+        Some((command, rest))
+            if matches!(
+                command.as_str(),
+                "verify" | "fetch" | "submit" | "examples" | "new-day" | "generate" | "explain"
+            ) =>
+        {
+            (command.as_str(), rest)
+        }
+        _ => ("run", &args[..]),
+    };
+    if command == "generate" {
+        return match generate(args) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                error!("{}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let inputs = match Inputs::from_env() {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            error!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if command == "explain" {
+        return match explain(&inputs, args, format.as_deref()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                error!("{}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    let format = match report_format(format.as_deref()) {
+        Ok(format) => format,
+        Err(e) => {
+            error!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if command == "submit" {
+        return match submit(&inputs, args) {
+            Ok(outcome @ Outcome::Correct) => {
+                info!("{}", outcome);
+                ExitCode::SUCCESS
+            }
+            Ok(outcome) => {
+                warn!("{}", outcome);
+                ExitCode::FAILURE
+            }
+            Err(e) => {
+                error!("{}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if command == "new-day" {
+        return match new_day(&inputs, args) {
+            Ok(written) => {
+                for path in written {
+                    info!("Wrote {}", path.display());
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                error!("{}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let days = match selected(args) {
+        Ok(days) => days,
+        Err(e) => {
+            error!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if command == "examples" {
+        // Most candidate blocks are not valid input and make the solutions
+        // panic, which is expected here.
+        panic::set_hook(Box::new(|_| {}));
+        let mut status = ExitCode::SUCCESS;
+        for day in days {
+            let directory = day.directory(runner::root());
+            let result = examples::extract(&directory, day).and_then(|examples| {
+                for example in examples.iter().filter(|example| !example.confirmed) {
+                    warn!(
+                        "Day {} part {}: the solution does not give {} for the example",
+                        day.day, example.part, example.answer
+                    );
+                }
+                let count = examples.len();
+                Examples { examples }
+                    .save(directory.join("examples.toml"))
+                    .map(|_| count)
+            });
+            match result {
+                Ok(count) => info!("Day {}: {} examples", day.day, count),
+                Err(e) => {
+                    error!("Day {}: {}", day.day, e);
+                    status = ExitCode::FAILURE;
+                }
+            }
+        }
+        return status;
+    }
+
+    if command == "fetch" {
+        let mut status = ExitCode::SUCCESS;
+        for day in days {
+            match inputs.resolve(day.day) {
+                Ok(path) => info!("Day {}: {}", day.day, path.display()),
+                Err(e) => {
+                    error!("Day {}: {}", day.day, e);
+                    status = ExitCode::FAILURE;
+                }
+            }
+        }
+        return status;
+    }
+
+    let report = verify::verify(runner::root(), &inputs, days);
+    if command == "verify" || format != Format::Text {
+        if let Err(e) = output::write(&report, format, io::stdout().lock()) {
+            error!("{}", e);
+            return ExitCode::FAILURE;
+        }
+        return if command == "run" || report.passed(allow_missing) {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        };
+    }
+
+    for check in &report.checks {
+        match &check.answer {
+            Some(answer) => match &check.allocations {
+                Some(allocations) => info!(
+                    "Day {} part {}: {} ({} allocs, {} B allocated, {} B peak)",
+                    check.day,
+                    check.part,
+                    answer,
+                    allocations.count,
+                    allocations.bytes,
+                    allocations.peak
+                ),
+                None => info!("Day {} part {}: {}", check.day, check.part, answer),
+            },
+            None => error!("Day {} part {}: {}", check.day, check.part, check.outcome),
+        }
+    }
+    for (day, reason) in &report.skipped {
+        error!("Day {}: {}", day, reason);
+    }
+    ExitCode::SUCCESS
+}
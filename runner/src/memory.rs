@@ -0,0 +1,38 @@
+use std::fs;
+
+/// The most memory the process has had resident since it started or since
+/// the last [`reset_peak`], in bytes. Only Linux reports it, through the
+/// `VmHWM` line of `/proc/self/status`.
+pub fn peak_resident() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|value| value.trim().strip_suffix("kB"))
+        .and_then(|kilobytes| kilobytes.trim().parse::<u64>().ok())
+        .map(|kilobytes| kilobytes * 1024)
+}
+
+/// Lowers the peak to what is resident now, so the next [`peak_resident`]
+/// covers only what ran in between. Returns `false` where that is
+/// unsupported or not permitted, the peak then still covers everything that
+/// ran before.
+pub fn reset_peak() -> bool {
+    fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_peak_resident() {
+        reset_peak();
+        let before = peak_resident().unwrap();
+        let buffer = std::hint::black_box(vec![1u8; 64 << 20]);
+        let after = peak_resident().unwrap();
+        assert!(after >= before + (32 << 20), "{} -> {}", before, after);
+        drop(buffer);
+    }
+}
@@ -0,0 +1,209 @@
+use std::{io, str::FromStr};
+
+use serde::Serialize;
+
+use crate::verify::{Check, Outcome, Report};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    JsonLines,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::JsonLines),
+            _ => Err(format!(
+                "Unknown format {:?}, expected text, json or jsonl",
+                s
+            )),
+        }
+    }
+}
+
+/// One part, or one skipped day with `part` left out, as written by the
+/// JSON formats. The fields are in a fixed order so that runs diff cleanly.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Record<'a> {
+    pub day: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part: Option<u8>,
+    pub answer: Option<&'a str>,
+    /// The Rust type of the answer, e.g. `u32`.
+    #[serde(rename = "type")]
+    pub kind: Option<&'static str>,
+    /// `correct`, `mismatch`, `missing`, `failed` or `skipped`.
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'a str>,
+    pub duration_ns: Option<u64>,
+    pub peak_memory_bytes: Option<u64>,
+    /// The heap use of the part, with `--allocations` only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocated_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_heap_bytes: Option<u64>,
+    /// SHA-256 of the input.
+    pub input_hash: Option<&'a str>,
+}
+
+impl<'a> From<&'a Check> for Record<'a> {
+    fn from(check: &'a Check) -> Self {
+        let (status, expected, error) = match &check.outcome {
+            Outcome::Correct => ("correct", None, None),
+            Outcome::Mismatch { expected } => ("mismatch", Some(expected.as_str()), None),
+            Outcome::Missing => ("missing", None, None),
+            Outcome::Failed(error) => ("failed", None, Some(error.as_str())),
+        };
+        Self {
+            day: check.day,
+            part: Some(check.part),
+            answer: check.answer.as_ref().map(|answer| answer.value.as_str()),
+            kind: check.answer.as_ref().map(|answer| answer.kind),
+            status,
+            expected,
+            error,
+            duration_ns: Some(check.duration.as_nanos() as u64),
+            peak_memory_bytes: check.peak_memory,
+            allocations: check.allocations.map(|allocations| allocations.count),
+            allocated_bytes: check.allocations.map(|allocations| allocations.bytes),
+            peak_heap_bytes: check.allocations.map(|allocations| allocations.peak),
+            input_hash: Some(&check.input_hash),
+        }
+    }
+}
+
+impl<'a> Record<'a> {
+    fn skipped(day: u8, reason: &'a str) -> Self {
+        Self {
+            day,
+            part: None,
+            answer: None,
+            kind: None,
+            status: "skipped",
+            expected: None,
+            error: Some(reason),
+            duration_ns: None,
+            peak_memory_bytes: None,
+            allocations: None,
+            allocated_bytes: None,
+            peak_heap_bytes: None,
+            input_hash: None,
+        }
+    }
+}
+
+/// Every check of `report` followed by the skipped days.
+pub fn records(report: &Report) -> Vec<Record<'_>> {
+    report
+        .checks
+        .iter()
+        .map(Record::from)
+        .chain(
+            report
+                .skipped
+                .iter()
+                .map(|(day, reason)| Record::skipped(*day, reason)),
+        )
+        .collect()
+}
+
+/// Writes `report` as the table `verify` prints, as a JSON array of records
+/// or as one record per line.
+pub fn write(report: &Report, format: Format, mut writer: impl io::Write) -> io::Result<()> {
+    match format {
+        Format::Text => write!(writer, "{}", report)?,
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut writer, &records(report))?;
+            writeln!(writer)?;
+        }
+        Format::JsonLines => {
+            for record in records(report) {
+                serde_json::to_writer(&mut writer, &record)?;
+                writeln!(writer)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{allocations::Allocations, days::Answer};
+
+    use super::*;
+
+    fn report() -> Report {
+        Report {
+            checks: vec![
+                Check {
+                    day: 1,
+                    part: 1,
+                    answer: Some(Answer::new(3u32)),
+                    duration: Duration::from_micros(5),
+                    peak_memory: Some(4096),
+                    allocations: Some(Allocations {
+                        count: 2,
+                        bytes: 96,
+                        peak: 64,
+                    }),
+                    input_hash: "abc".to_string(),
+                    outcome: Outcome::Correct,
+                },
+                Check {
+                    day: 1,
+                    part: 2,
+                    answer: None,
+                    duration: Duration::from_micros(1),
+                    peak_memory: None,
+                    allocations: None,
+                    input_hash: "abc".to_string(),
+                    outcome: Outcome::Failed("panicked: boom".to_string()),
+                },
+            ],
+            skipped: vec![(2, "no input".to_string())],
+        }
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let mut output = Vec::new();
+        write(&report(), Format::JsonLines, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                r#"{"day":1,"part":1,"answer":"3","type":"u32","status":"correct","duration_ns":5000,"peak_memory_bytes":4096,"allocations":2,"allocated_bytes":96,"peak_heap_bytes":64,"input_hash":"abc"}"#,
+                r#"{"day":1,"part":2,"answer":null,"type":null,"status":"failed","error":"panicked: boom","duration_ns":1000,"peak_memory_bytes":null,"input_hash":"abc"}"#,
+                r#"{"day":2,"answer":null,"type":null,"status":"skipped","error":"no input","duration_ns":null,"peak_memory_bytes":null,"input_hash":null}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let mut output = Vec::new();
+        write(&report(), Format::Json, &mut output).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert_eq!(json[0]["answer"], "3");
+        assert_eq!(json[1]["status"], "failed");
+        assert_eq!(json[2]["day"], 2);
+
+        assert_eq!("jsonl".parse(), Ok(Format::JsonLines));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
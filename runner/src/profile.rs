@@ -0,0 +1,138 @@
+//! Logging and the opt-in span exporters.
+//!
+//! Every day is instrumented the same way, so any of them can be profiled
+//! without code changes:
+//!
+//! - the runner opens a `solve` span with `day` and `part` around each part,
+//! - a part's entry point, usually `run`, is instrumented and takes the raw
+//!   input,
+//! - the function reading the input into the day's model is instrumented as
+//!   `parse` and called from the entry point, so `parse` always nests under it,
+//! - later phases worth telling apart, like building a function or a search,
+//!   get a span named after the phase.
+//!
+//! These spans are at `INFO`. Functions called per line, step or cell are
+//! not instrumented, a span each would cost more than the work they do.
+
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use tracing::Subscriber;
+use tracing_chrome::ChromeLayerBuilder;
+use tracing_flame::FlameLayer;
+use tracing_subscriber::{
+    filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
+    Registry,
+};
+
+/// Where to write the spans of a run, besides logging as `RUST_LOG` says.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Profile {
+    /// A Chrome trace, for `chrome://tracing` or Perfetto.
+    pub chrome_trace: Option<PathBuf>,
+    /// Folded stacks, for `inferno-flamegraph` or `flamegraph.pl`.
+    pub folded: Option<PathBuf>,
+}
+
+/// Flushes the exporters when dropped, keep it until the end of `main`.
+#[must_use]
+pub struct Guard {
+    _chrome: Option<tracing_chrome::FlushGuard>,
+    _flame: Option<tracing_flame::FlushGuard<BufWriter<File>>>,
+}
+
+impl Profile {
+    pub fn with_chrome_trace(mut self, path: impl Into<PathBuf>) -> Self {
+        self.chrome_trace = Some(path.into());
+        self
+    }
+
+    pub fn with_folded(mut self, path: impl Into<PathBuf>) -> Self {
+        self.folded = Some(path.into());
+        self
+    }
+
+    fn subscriber(&self) -> Result<(impl Subscriber + Send + Sync, Guard), String> {
+        let (chrome, chrome_guard) = match &self.chrome_trace {
+            Some(path) => {
+                let (layer, guard) = ChromeLayerBuilder::new()
+                    .file(path)
+                    .include_args(true)
+                    .build();
+                (Some(layer.with_filter(LevelFilter::INFO)), Some(guard))
+            }
+            None => (None, None),
+        };
+        let (flame, flame_guard) = match &self.folded {
+            Some(path) => {
+                let (layer, guard) = FlameLayer::with_file(path)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                // Without lines, a span folds into one frame wherever it is.
+                let layer = layer.with_file_and_line(false);
+                (Some(layer.with_filter(LevelFilter::INFO)), Some(guard))
+            }
+            None => (None, None),
+        };
+        let subscriber = Registry::default()
+            .with(fmt::layer().with_filter(EnvFilter::from_default_env()))
+            .with(chrome)
+            .with(flame);
+        let guard = Guard {
+            _chrome: chrome_guard,
+            _flame: flame_guard,
+        };
+        Ok((subscriber, guard))
+    }
+
+    /// Installs the global subscriber.
+    pub fn init(&self) -> Result<Guard, String> {
+        let (subscriber, guard) = self.subscriber()?;
+        subscriber.try_init().map_err(|e| e.to_string())?;
+        Ok(guard)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use tracing::info_span;
+
+    use super::*;
+
+    #[test]
+    fn test_exporters() {
+        let directory = env::temp_dir().join(format!("runner-profile-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let profile = Profile::default()
+            .with_chrome_trace(directory.join("trace.json"))
+            .with_folded(directory.join("stacks.folded"));
+
+        let (subscriber, guard) = profile.subscriber().unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            info_span!("solve", day = 1, part = 2).in_scope(|| {
+                info_span!("parse").in_scope(|| {});
+                tracing::debug_span!("step").in_scope(|| {});
+            });
+        });
+        drop(guard);
+
+        let trace = fs::read_to_string(directory.join("trace.json")).unwrap();
+        let events: serde_json::Value = serde_json::from_str(&trace).unwrap();
+        let names: Vec<&str> = events
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|event| event["name"].as_str())
+            .collect();
+        assert!(names.contains(&"solve"));
+        assert!(names.contains(&"parse"));
+        assert!(!names.contains(&"step"));
+
+        let folded = fs::read_to_string(directory.join("stacks.folded")).unwrap();
+        assert!(folded
+            .lines()
+            .any(|line| line.contains("::solve; ") && line.contains("::parse ")));
+        assert!(!folded.contains("step"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,344 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{days::Day, examples};
+
+const CARGO_TOML: &str = include_str!("../templates/Cargo.toml");
+const LIB_RS: &str = include_str!("../templates/lib.rs");
+const MAIN_RS: &str = include_str!("../templates/main.rs");
+const PART_RS: &str = include_str!("../templates/part.rs");
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// The text of every `<article>` of a puzzle page, one per part. Paragraphs
+/// and list items become lines, `<pre>` blocks are kept verbatim, and
+/// blocks are separated by a blank line like the statements already in the
+/// repository.
+pub fn statements(html: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<article") {
+        let Some(end) = rest[start..].find("</article>") else {
+            break;
+        };
+        statements.push(article(&rest[start..start + end]));
+        rest = &rest[start + end..];
+    }
+    statements
+}
+
+fn article(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_pre = false;
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            push_text(&mut text, rest, in_pre);
+            break;
+        };
+        push_text(&mut text, &rest[..open], in_pre);
+        let Some(close) = rest[open..].find('>') else {
+            break;
+        };
+        let tag = rest[open + 1..open + close].trim().to_lowercase();
+        let name = tag.split_whitespace().next().unwrap_or("");
+        match name {
+            "pre" => in_pre = true,
+            "/pre" => {
+                in_pre = false;
+                text.push_str("\n\n");
+            }
+            "/h2" | "/p" | "/ul" => text.push_str("\n\n"),
+            "/li" => text.push('\n'),
+            _ => {}
+        }
+        rest = &rest[open + close + 1..];
+    }
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
+
+fn push_text(text: &mut String, raw: &str, in_pre: bool) {
+    let decoded = decode_entities(raw);
+    if in_pre {
+        text.push_str(&decoded);
+    } else {
+        let collapsed = decoded.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !collapsed.is_empty() {
+            let glue = decoded.starts_with(char::is_whitespace)
+                && !text.is_empty()
+                && !text.ends_with(char::is_whitespace);
+            if glue {
+                text.push(' ');
+            }
+            text.push_str(&collapsed);
+            if decoded.ends_with(char::is_whitespace) {
+                text.push(' ');
+            }
+        }
+    }
+}
+
+fn render(template: &str, replacements: &[(&str, &str)]) -> String {
+    replacements
+        .iter()
+        .fold(template.to_string(), |rendered, (placeholder, value)| {
+            rendered.replace(&format!("{{{{{}}}}}", placeholder), value)
+        })
+}
+
+/// `part_one.rs` or `part_two.rs`: the statement in the leading comment, a
+/// `run` stub and a test against the statement's example answer, ignored
+/// until the part is solved. Without an answer the test asserts `0`, to be
+/// filled in by hand.
+fn part(part: u8, statement: Option<&str>) -> String {
+    let statement = statement.unwrap_or("").replace("*/", "* /");
+    let answer = examples::answer(&statement);
+    render(
+        PART_RS,
+        &[
+            ("statement", &statement),
+            ("test", &format!("part_{}", part)),
+            ("answer", answer.as_deref().unwrap_or("0")),
+        ],
+    )
+}
+
+/// The first example of a statement, which the generated tests read.
+fn test_data(statements: &[String]) -> String {
+    statements
+        .iter()
+        .flat_map(|statement| examples::blocks(statement))
+        .find(|block| block.intro.to_lowercase().contains("example"))
+        .map(|block| block.input)
+        .unwrap_or_default()
+}
+
+fn write(path: PathBuf, contents: &str) -> Result<PathBuf, String> {
+    fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Creates `day_<day>` below `root` from the templates and registers it with
+/// the runner. `html` is the saved puzzle page the statements come from,
+/// `input` the puzzle input if it is known already. The parts start out
+/// unsolved, so the `examples.toml` written alongside holds no examples
+/// until `runner examples <day>` is run on the solutions.
+pub fn new_day(
+    root: &Path,
+    day: u8,
+    html: Option<&str>,
+    input: Option<&str>,
+) -> Result<Vec<PathBuf>, String> {
+    let directory = root.join(format!("day_{}", day));
+    if directory.exists() {
+        return Err(format!("{} already exists", directory.display()));
+    }
+    let source = directory.join("src");
+    fs::create_dir_all(&source).map_err(|e| format!("{}: {}", source.display(), e))?;
+
+    let statements = html.map(statements).unwrap_or_default();
+    let name = day.to_string();
+    let mut written = vec![
+        write(
+            directory.join("Cargo.toml"),
+            &render(CARGO_TOML, &[("day", &name)]),
+        )?,
+        write(source.join("lib.rs"), LIB_RS)?,
+        write(source.join("main.rs"), &render(MAIN_RS, &[("day", &name)]))?,
+        write(
+            source.join("part_one.rs"),
+            &part(1, statements.first().map(String::as_str)),
+        )?,
+        write(
+            source.join("part_two.rs"),
+            &part(2, statements.get(1).map(String::as_str)),
+        )?,
+        write(directory.join("test_data.txt"), &test_data(&statements))?,
+        write(directory.join("input.txt"), input.unwrap_or(""))?,
+    ];
+    let unsolved = Day {
+        day,
+        part_one: None,
+        part_two: None,
+    };
+    let fixtures = directory.join("examples.toml");
+    examples::Examples {
+        examples: examples::extract(&directory, &unsolved)?,
+    }
+    .save(&fixtures)?;
+    written.push(fixtures);
+    written.extend(register(root, day)?);
+    Ok(written)
+}
+
+/// Adds the day as a dependency of the runner and to its list of days,
+/// keeping both ordered by day. Both parts are registered as `None` until
+/// their stubs are replaced by solutions.
+pub fn register(root: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    let manifest = root.join("runner").join("Cargo.toml");
+    let contents =
+        fs::read_to_string(&manifest).map_err(|e| format!("{}: {}", manifest.display(), e))?;
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let is_day = |line: &str| line.starts_with("day_");
+    let number = |line: &str| {
+        line.trim_start_matches("day_")
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .and_then(|number| number.parse::<u8>().ok())
+    };
+    let position = lines
+        .iter()
+        .position(|line| is_day(line) && number(line) > Some(day))
+        .or_else(|| {
+            lines
+                .iter()
+                .rposition(|line| is_day(line))
+                .map(|last| last + 1)
+        })
+        .ok_or_else(|| format!("{}: no day dependencies to add to", manifest.display()))?;
+    lines.insert(
+        position,
+        format!("day_{} = {{ path = \"../day_{}\" }}", day, day),
+    );
+    fs::write(&manifest, lines.join("\n") + "\n")
+        .map_err(|e| format!("{}: {}", manifest.display(), e))?;
+
+    let days = root.join("runner").join("src").join("days.rs");
+    let contents = fs::read_to_string(&days).map_err(|e| format!("{}: {}", days.display(), e))?;
+    let entry = format!(
+        "    Day {{\n        day: {day},\n        // Still the stubs returning `0`.\n        part_one: None,\n        part_two: None,\n    }},\n",
+        day = day
+    );
+    let start = contents
+        .find("pub const DAYS: &[Day] = &[\n")
+        .ok_or_else(|| format!("{}: no DAYS to add to", days.display()))?;
+    let end = start
+        + contents[start..]
+            .find("\n];")
+            .ok_or_else(|| format!("{}: DAYS is not closed", days.display()))?
+        + 1;
+    // Entries start with `    Day {`, insert before the first later day.
+    let insert_at = contents[start..end]
+        .match_indices("    Day {\n        day: ")
+        .map(|(offset, matched)| {
+            let after = start + offset + matched.len();
+            let number: String = contents[after..]
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect();
+            (start + offset, number.parse::<u8>().unwrap_or(0))
+        })
+        .find(|&(_, number)| number > day)
+        .map_or(end, |(offset, _)| offset);
+    let mut contents = contents;
+    contents.insert_str(insert_at, &entry);
+    fs::write(&days, contents).map_err(|e| format!("{}: {}", days.display(), e))?;
+    Ok(vec![manifest, days])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 19: Aplenty ---</h2><p>The Elves of Gear Island are already waiting.</p>
+<p>For example:</p>
+<pre><code>px{a&lt;2006:qkq,m&gt;2090:A,rfg}
+pv{a&gt;1716:R,A}
+</code></pre>
+<p>Adding all of the ratings for <em>all</em> of the accepted parts gives the sum total of <code><em>19114</em></code>.</p>
+<p>Sort through all of the parts you've been given; <em>what do you get if you add together all of the rating numbers for all of the parts that ultimately get accepted?</em></p>
+</article>
+<p>Your puzzle answer was <code>1</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>In the above example, there are <code><em>167409079868000</em></code> distinct combinations.</p><p><em>How many distinct combinations will be accepted?</em></p></article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_statements() {
+        let statements = statements(PAGE);
+        assert_eq!(statements.len(), 2);
+        assert_eq!(
+            statements[0],
+            "--- Day 19: Aplenty ---\n\nThe Elves of Gear Island are already waiting.\n\nFor example:\n\npx{a<2006:qkq,m>2090:A,rfg}\npv{a>1716:R,A}\n\nAdding all of the ratings for all of the accepted parts gives the sum total of 19114.\n\nSort through all of the parts you've been given; what do you get if you add together all of the rating numbers for all of the parts that ultimately get accepted?"
+        );
+        assert_eq!(
+            examples::answer(&statements[1]),
+            Some("167409079868000".to_string())
+        );
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join(format!("runner-scaffold-{}", std::process::id()));
+        fs::remove_dir_all(&root).ok();
+        let runner = root.join("runner");
+        fs::create_dir_all(runner.join("src")).unwrap();
+        fs::write(
+            runner.join("Cargo.toml"),
+            include_str!("../Cargo.toml").replace("day_18 = ", "day_20 = "),
+        )
+        .unwrap();
+        fs::write(runner.join("src").join("days.rs"), include_str!("days.rs")).unwrap();
+
+        new_day(&root, 19, Some(PAGE), Some("in{x<4000:R,A}\n")).unwrap();
+        let directory = root.join("day_19");
+        let part_one = fs::read_to_string(directory.join("src/part_one.rs")).unwrap();
+        assert!(part_one.starts_with("/*\n--- Day 19: Aplenty ---\n"));
+        assert!(part_one.contains("#[ignore = \"not solved yet\"]\n    fn part_1()"));
+        assert!(part_one.contains("assert_eq!(output, 19114);"));
+        assert_eq!(
+            fs::read_to_string(directory.join("test_data.txt")).unwrap(),
+            "px{a<2006:qkq,m>2090:A,rfg}\npv{a>1716:R,A}\n"
+        );
+        // What `test_fixtures_are_current` checks once the day is registered.
+        let unsolved = Day {
+            day: 19,
+            part_one: None,
+            part_two: None,
+        };
+        assert!(directory.join("examples.toml").exists());
+        assert_eq!(
+            examples::Examples::load(directory.join("examples.toml")).unwrap(),
+            examples::Examples {
+                examples: examples::extract(&directory, &unsolved).unwrap()
+            }
+        );
+        assert!(fs::read_to_string(directory.join("src/main.rs"))
+            .unwrap()
+            .starts_with("use day_19::{part_one, part_two};"));
+
+        let manifest = fs::read_to_string(runner.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains(
+            "day_17 = { path = \"../day_17\" }\nday_19 = { path = \"../day_19\" }\nday_20 = "
+        ));
+        let days = fs::read_to_string(runner.join("src/days.rs")).unwrap();
+        let registered = days.find("day: 19,").unwrap();
+        assert!(days.find("day: 18,").unwrap() < registered);
+        assert!(days[registered..].starts_with(
+            "day: 19,\n        // Still the stubs returning `0`.\n        part_one: None,\n        part_two: None,\n    },\n];"
+        ));
+
+        assert!(new_day(&root, 19, None, None).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,422 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::http::{Backend, Client};
+
+/// What the site made of a submitted answer.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without saying in which direction.
+    Wrong,
+    /// Submitted too soon after the previous answer, nothing was checked.
+    RateLimited {
+        wait: Option<String>,
+    },
+    /// The part was solved before, nothing was checked.
+    AlreadySolved,
+    /// A page none of the above could be recognised in.
+    Unknown(String),
+}
+
+impl Outcome {
+    /// Whether the site judged the answer, rather than turning it away.
+    pub fn is_verdict(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Correct | Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong
+        )
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "too high"),
+            Outcome::TooLow => write!(f, "too low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited, {} left to wait", wait)
+            }
+            Outcome::RateLimited { wait: None } => write!(f, "rate limited"),
+            Outcome::AlreadySolved => write!(f, "already solved"),
+            Outcome::Unknown(text) => write!(f, "unrecognised response: {}", text),
+        }
+    }
+}
+
+/// The text of the page's `<article>`, or of the whole page without one,
+/// with the tags dropped and the whitespace collapsed.
+fn article_text(body: &str) -> String {
+    let article = match (body.find("<article"), body.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &body[start..end],
+        _ => body,
+    };
+    let mut text = String::new();
+    let mut in_tag = false;
+    for character in article.chars() {
+        match character {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(character),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn parse_response(body: &str) -> Outcome {
+    let text = article_text(body);
+    if text.contains("That's the right answer") {
+        Outcome::Correct
+    } else if text.contains("your answer is too high") {
+        Outcome::TooHigh
+    } else if text.contains("your answer is too low") {
+        Outcome::TooLow
+    } else if text.contains("That's not the right answer") {
+        Outcome::Wrong
+    } else if text.contains("You gave an answer too recently") {
+        let wait = text
+            .split("You have ")
+            .nth(1)
+            .and_then(|rest| rest.split(" left to wait").next())
+            .map(|wait| wait.to_string());
+        Outcome::RateLimited { wait }
+    } else if text.contains("Did you already complete it") {
+        Outcome::AlreadySolved
+    } else {
+        Outcome::Unknown(text)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Submission {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Why an answer is not worth sending.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Refusal {
+    AlreadySolved {
+        answer: String,
+    },
+    KnownWrong {
+        outcome: Outcome,
+    },
+    /// At or above an answer that was too high.
+    AboveBound {
+        too_high: String,
+    },
+    /// At or below an answer that was too low.
+    BelowBound {
+        too_low: String,
+    },
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::AlreadySolved { answer } => write!(f, "already solved with {}", answer),
+            Refusal::KnownWrong { outcome } => write!(f, "already submitted, it was {}", outcome),
+            Refusal::AboveBound { too_high } => {
+                write!(f, "{} was already too high", too_high)
+            }
+            Refusal::BelowBound { too_low } => write!(f, "{} was already too low", too_low),
+        }
+    }
+}
+
+/// Every answer the site judged, persisted as TOML.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct History {
+    #[serde(default, rename = "submission")]
+    pub submissions: Vec<Submission>,
+}
+
+impl History {
+    /// Reads `path`, treating a missing file as an empty history.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(input) => toml::from_str(&input).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let output = toml::to_string(self).map_err(|e| e.to_string())?;
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|e| format!("{}: {}", directory.display(), e))?;
+        }
+        fs::write(path, output).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Checks `answer` against what was submitted for the same part before.
+    /// Bounds only apply when both answers are integers.
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Result<(), Refusal> {
+        let answer = answer.trim();
+        let number = answer.parse::<i128>().ok();
+        for submission in self
+            .submissions
+            .iter()
+            .filter(|submission| submission.day == day && submission.part == part)
+        {
+            if submission.outcome == Outcome::Correct {
+                return Err(Refusal::AlreadySolved {
+                    answer: submission.answer.clone(),
+                });
+            }
+            if submission.answer == answer {
+                return Err(Refusal::KnownWrong {
+                    outcome: submission.outcome.clone(),
+                });
+            }
+            let previous = submission.answer.parse::<i128>().ok();
+            match (&submission.outcome, number, previous) {
+                (Outcome::TooHigh, Some(number), Some(previous)) if number >= previous => {
+                    return Err(Refusal::AboveBound {
+                        too_high: submission.answer.clone(),
+                    });
+                }
+                (Outcome::TooLow, Some(number), Some(previous)) if number <= previous => {
+                    return Err(Refusal::BelowBound {
+                        too_low: submission.answer.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `<data dir>/<year>/submissions.toml`.
+pub fn history_path(data_dir: impl AsRef<Path>, year: u16) -> PathBuf {
+    data_dir
+        .as_ref()
+        .join(year.to_string())
+        .join("submissions.toml")
+}
+
+/// Posts answers for one year, keeping the history next to the cached inputs.
+pub struct Submitter<'a, B: Backend> {
+    client: &'a Client<B>,
+    year: u16,
+    history: PathBuf,
+}
+
+impl<'a, B: Backend> Submitter<'a, B> {
+    pub fn new(client: &'a Client<B>, year: u16, history: impl Into<PathBuf>) -> Self {
+        Self {
+            client,
+            year,
+            history: history.into(),
+        }
+    }
+
+    /// Submits `answer` unless the history already rules it out. Verdicts are
+    /// recorded, responses that judged nothing are only returned.
+    pub fn submit(&self, day: u8, part: u8, answer: &str) -> Result<Outcome, String> {
+        let answer = answer.trim();
+        let mut history = History::load(&self.history)?;
+        history
+            .check(day, part, answer)
+            .map_err(|refusal| format!("Not submitting {}: {}", answer, refusal))?;
+
+        let response = self.client.post_form(
+            &format!("/{}/day/{}/answer", self.year, day),
+            vec![
+                ("level".to_string(), part.to_string()),
+                ("answer".to_string(), answer.to_string()),
+            ],
+        )?;
+        if response.status != 200 {
+            return Err(format!(
+                "submitting day {} part {} failed with status {}: {}",
+                day,
+                part,
+                response.status,
+                response.body.trim()
+            ));
+        }
+
+        let outcome = parse_response(&response.body);
+        if outcome.is_verdict() {
+            history.submissions.push(Submission {
+                day,
+                part,
+                answer: answer.to_string(),
+                outcome: outcome.clone(),
+            });
+            history.save(&self.history)?;
+        }
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, time::Duration};
+
+    use crate::mock::MockServer;
+
+    use super::*;
+
+    fn page(text: &str) -> String {
+        format!(
+            "<html><body><main><article><p>{}</p></article></main></body></html>",
+            text
+        )
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            parse_response(&page(
+                "That's the right answer! You are <em>one gold star</em> closer."
+            )),
+            Outcome::Correct
+        );
+        assert_eq!(
+            parse_response(&page("That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data.")),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            parse_response(&page("That's not the right answer.  If you're stuck, ...")),
+            Outcome::Wrong
+        );
+        assert_eq!(
+            parse_response(&page("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 37s left to wait. <a href=\"/2023/day/1\">[Return to Day 1]</a>")),
+            Outcome::RateLimited {
+                wait: Some("37s".to_string())
+            }
+        );
+        assert_eq!(
+            parse_response(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Outcome::AlreadySolved
+        );
+        assert!(matches!(
+            parse_response("<html></html>"),
+            Outcome::Unknown(_)
+        ));
+    }
+
+    #[test]
+    fn test_check() {
+        let submission = |answer: &str, outcome| Submission {
+            day: 1,
+            part: 1,
+            answer: answer.to_string(),
+            outcome,
+        };
+        let history = History {
+            submissions: vec![
+                submission("500", Outcome::TooHigh),
+                submission("100", Outcome::TooLow),
+                submission("250", Outcome::Wrong),
+            ],
+        };
+        assert_eq!(history.check(1, 1, "300"), Ok(()));
+        assert_eq!(history.check(1, 2, "500"), Ok(()));
+        assert_eq!(
+            history.check(1, 1, "250"),
+            Err(Refusal::KnownWrong {
+                outcome: Outcome::Wrong
+            })
+        );
+        assert_eq!(
+            history.check(1, 1, "600"),
+            Err(Refusal::AboveBound {
+                too_high: "500".to_string()
+            })
+        );
+        assert_eq!(
+            history.check(1, 1, "99"),
+            Err(Refusal::BelowBound {
+                too_low: "100".to_string()
+            })
+        );
+        assert_eq!(history.check(1, 1, "abc"), Ok(()));
+
+        let mut solved = history.clone();
+        solved.submissions.push(submission("321", Outcome::Correct));
+        assert_eq!(
+            solved.check(1, 1, "300"),
+            Err(Refusal::AlreadySolved {
+                answer: "321".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = MockServer::start(|request| {
+            let text = match request.body.as_str() {
+                "level=1&answer=500" => "That's not the right answer; your answer is too high.",
+                "level=1&answer=300" => "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 2s left to wait.",
+                _ => "That's the right answer!",
+            };
+            (200, page(text))
+        });
+        let client = Client::new("abc")
+            .with_base_url(server.url())
+            .with_min_interval(Duration::ZERO);
+        let directory = env::temp_dir().join(format!("runner-submit-{}", std::process::id()));
+        fs::remove_dir_all(&directory).ok();
+        let history = history_path(&directory, 2023);
+        let submitter = Submitter::new(&client, 2023, &history);
+
+        assert_eq!(submitter.submit(1, 1, "500"), Ok(Outcome::TooHigh));
+        assert!(submitter
+            .submit(1, 1, "700")
+            .unwrap_err()
+            .contains("too high"));
+        assert_eq!(
+            submitter.submit(1, 1, "300"),
+            Ok(Outcome::RateLimited {
+                wait: Some("1m 2s".to_string())
+            })
+        );
+        assert_eq!(
+            submitter.submit(1, 1, "300"),
+            Ok(Outcome::RateLimited {
+                wait: Some("1m 2s".to_string())
+            })
+        );
+        assert_eq!(submitter.submit(1, 1, "299"), Ok(Outcome::Correct));
+        assert_eq!(server.requests().len(), 4);
+
+        let saved = History::load(&history).unwrap();
+        let outcomes: Vec<(&str, &Outcome)> = saved
+            .submissions
+            .iter()
+            .map(|submission| (submission.answer.as_str(), &submission.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [("500", &Outcome::TooHigh), ("299", &Outcome::Correct)]
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    fmt, fs, io, panic,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    allocations::{self, Allocations},
+    answers::Answers,
+    days::{Answer, Day, Solve},
+    http::Backend,
+    inputs::Inputs,
+    memory,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Correct,
+    Mismatch {
+        expected: String,
+    },
+    /// Solved, but `answers.toml` holds nothing to compare with.
+    Missing,
+    /// The part returned an error or panicked.
+    Failed(String),
+}
+
+impl Outcome {
+    pub fn is_regression(&self) -> bool {
+        matches!(self, Outcome::Mismatch { .. } | Outcome::Failed(_))
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "ok"),
+            Outcome::Mismatch { expected } => write!(f, "MISMATCH, expected {}", expected),
+            Outcome::Missing => write!(f, "no recorded answer"),
+            Outcome::Failed(error) => write!(f, "FAILED: {}", error),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<Answer>,
+    pub duration: Duration,
+    /// Peak resident memory of the process while the part ran, where the
+    /// platform reports it and lets the peak be reset before each part.
+    pub peak_memory: Option<u64>,
+    /// Heap use of the part, when allocations are counted.
+    pub allocations: Option<Allocations>,
+    /// [`input_hash`] of the input the part ran on.
+    pub input_hash: String,
+    pub outcome: Outcome,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+    /// Days that could not be run at all, with the reason.
+    pub skipped: Vec<(u8, String)>,
+}
+
+impl Report {
+    pub fn regressions(&self) -> impl Iterator<Item = &Check> {
+        self.checks
+            .iter()
+            .filter(|check| check.outcome.is_regression())
+    }
+
+    /// Parts solved without an answer in `answers.toml` to compare with.
+    pub fn missing(&self) -> impl Iterator<Item = &Check> {
+        self.checks
+            .iter()
+            .filter(|check| check.outcome == Outcome::Missing)
+    }
+
+    /// Whether nothing regressed. Parts without a recorded answer and days
+    /// that could not be run fail too, unless `allow_missing` is set.
+    pub fn passed(&self, allow_missing: bool) -> bool {
+        self.regressions().next().is_none()
+            && (allow_missing || (self.missing().next().is_none() && self.skipped.is_empty()))
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let answer = check.answer.as_ref().map_or("-", |answer| &answer.value);
+            write!(
+                f,
+                "day {:>2} part {}  {:>16}  {:>10.3?}  ",
+                check.day, check.part, answer, check.duration
+            )?;
+            if let Some(allocations) = &check.allocations {
+                write!(f, "{}  ", allocations)?;
+            }
+            writeln!(f, "{}", check.outcome)?;
+        }
+        for (day, reason) in &self.skipped {
+            writeln!(f, "day {:>2} skipped: {}", day, reason)?;
+        }
+        let total: Duration = self.checks.iter().map(|check| check.duration).sum();
+        writeln!(
+            f,
+            "{} checked, {} regressed, {} without answer, {} days skipped in {:.3?}",
+            self.checks.len(),
+            self.regressions().count(),
+            self.missing().count(),
+            self.skipped.len(),
+            total
+        )
+    }
+}
+
+/// Runs `solve` on `input`, turning a panic into an error.
+pub fn run(solve: Solve, input: &str) -> (Result<Answer, String>, Duration) {
+    let start = Instant::now();
+    let result = panic::catch_unwind(|| solve(input)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(format!("panicked: {}", message))
+    });
+    (result, start.elapsed())
+}
+
+/// The SHA-256 of `input` in hex, as `sha256sum` prints it, to tell which
+/// input an answer belongs to without sharing the input.
+pub fn input_hash(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// The `input.txt` of the day's crate if there is one, the cached or fetched
+/// input otherwise.
+pub fn input<B: Backend>(directory: &Path, inputs: &Inputs<B>, day: u8) -> Result<String, String> {
+    match fs::read_to_string(directory.join("input.txt")) {
+        Ok(input) => Ok(input),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => inputs.read(day),
+        Err(e) => Err(format!("{}: {}", directory.join("input.txt").display(), e)),
+    }
+}
+
+/// Solves every part of `days` and compares the results with the day's
+/// `answers.toml` below `root`.
+pub fn verify<'a, B: Backend>(
+    root: impl AsRef<Path>,
+    inputs: &Inputs<B>,
+    days: impl IntoIterator<Item = &'a Day>,
+) -> Report {
+    let mut report = Report::default();
+    for day in days {
+        let directory = day.directory(&root);
+        let input = match input(&directory, inputs, day.day) {
+            Ok(input) => input,
+            Err(e) => {
+                report.skipped.push((day.day, e));
+                continue;
+            }
+        };
+        let input_hash = input_hash(&input);
+        let answers = Answers::load(directory.join("answers.toml"));
+        for (part, solve) in day.parts() {
+            let reset = memory::reset_peak();
+            allocations::reset();
+            let (result, duration) =
+                tracing::info_span!("solve", day = day.day, part).in_scope(|| run(solve, &input));
+            let allocations = allocations::snapshot();
+            // Without a reset the peak would include the parts run before.
+            let peak_memory = memory::peak_resident().filter(|_| reset);
+            let (answer, outcome) = match (result, &answers) {
+                (Err(error), _) => (None, Outcome::Failed(error)),
+                (Ok(answer), Err(error)) => (Some(answer), Outcome::Failed(error.clone())),
+                (Ok(answer), Ok(answers)) => {
+                    let outcome = match answers.get(part) {
+                        None => Outcome::Missing,
+                        Some(expected) if expected == answer.value => Outcome::Correct,
+                        Some(expected) => Outcome::Mismatch {
+                            expected: expected.to_string(),
+                        },
+                    };
+                    (Some(answer), outcome)
+                }
+            };
+            report.checks.push(Check {
+                day: day.day,
+                part,
+                answer,
+                duration,
+                peak_memory,
+                allocations,
+                input_hash: input_hash.clone(),
+                outcome,
+            });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::http::Ureq;
+
+    use super::*;
+
+    fn fixture(name: &str, answers: Option<&str>) -> std::path::PathBuf {
+        let root = env::temp_dir().join(format!("runner-verify-{}-{}", name, std::process::id()));
+        let directory = root.join("day_1");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("input.txt"), "1\n2\n3\n").unwrap();
+        if let Some(answers) = answers {
+            fs::write(directory.join("answers.toml"), answers).unwrap();
+        }
+        root
+    }
+
+    const DAY: Day = Day {
+        day: 1,
+        part_one: Some(|input| Ok(Answer::new(input.lines().count()))),
+        part_two: Some(|input| {
+            let sum: u32 = input.lines().map(|line| line.parse::<u32>().unwrap()).sum();
+            Ok(Answer::new(sum))
+        }),
+    };
+
+    #[test]
+    fn test_verify() {
+        let _lock = crate::allocations::TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let root = fixture("verify", Some("part_one = 3\npart_two = 7\n"));
+        let report = verify(&root, &Inputs::<Ureq>::new(2023, root.join("data")), [&DAY]);
+        let outcomes: Vec<&Outcome> = report.checks.iter().map(|check| &check.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [
+                &Outcome::Correct,
+                &Outcome::Mismatch {
+                    expected: "7".to_string()
+                }
+            ]
+        );
+        assert!(!report.passed(true));
+        assert_eq!(report.checks[1].answer.as_ref().unwrap().kind, "u32");
+        assert_eq!(
+            report.checks[1].input_hash,
+            "14c5e74c4b96ccef41cd94db73a9ec3348038ac094feca4fd897cecffa07cdae"
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_verify_missing_answers_and_input() {
+        let _lock = crate::allocations::TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let root = fixture("missing", Some("part_one = 3\n"));
+        let missing_input = Day { day: 2, ..DAY };
+        let inputs = Inputs::<Ureq>::new(2023, root.join("data"));
+        let report = verify(&root, &inputs, [&DAY, &missing_input]);
+        assert_eq!(report.checks[1].outcome, Outcome::Missing);
+        assert_eq!(report.missing().count(), 1);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].0, 2);
+        assert!(!report.passed(false));
+        assert!(report.passed(true));
+
+        let report = verify(&root, &inputs, [&missing_input]);
+        assert!(report.checks.is_empty());
+        assert!(!report.passed(false));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_panic_is_a_failure() {
+        let (result, _) = run(|_| panic!("boom"), "");
+        assert_eq!(result, Err("panicked: boom".to_string()));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod part_one;
+pub mod part_two;
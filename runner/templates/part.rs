@@ -0,0 +1,20 @@
+/*
+{{statement}}
+*/
+
+pub fn run(_input: &str) -> u32 {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn {{test}}() {
+        let input = include_str!("../test_data.txt");
+        let output = run(input);
+        assert_eq!(output, {{answer}});
+    }
+}